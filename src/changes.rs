@@ -0,0 +1,331 @@
+use crate::PreferencesType;
+use bevy::prelude::*;
+use bevy::reflect::{ReflectRef, VariantType};
+
+/// Event sent every time the preferences of type `T` change.
+///
+/// Unlike checking if [`crate::PreferencesResource`] is changed, this event is only sent
+/// when the value is actually different, no matter how it was modified.
+/// It's also triggered, so it can be observed using [`App::add_observer`].
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_simple_preferences::*;
+/// # #[derive(Reflect, Default)]
+/// # struct MyPreferences {
+/// #    volume: f32,
+/// # }
+/// App::new()
+///     .register_preferences::<MyPreferences>()
+///     .add_systems(Update, |mut changes: EventReader<PreferencesChanged<MyPreferences>>| {
+///         for change in changes.read() {
+///             info!("Volume changed from {} to {}", change.old.volume, change.new.volume);
+///         }
+///     })
+///     .add_observer(|trigger: Trigger<PreferencesChanged<MyPreferences>>| {
+///         info!("Volume changed to {}", trigger.new.volume);
+///     });
+/// ```
+#[derive(Event)]
+pub struct PreferencesChanged<T: PreferencesType> {
+    /// Value of the preferences before the change.
+    pub old: T,
+    /// Value of the preferences after the change.
+    pub new: T,
+    /// Paths of the fields that changed, as returned by [`changed_fields`].
+    pub changed_fields: Vec<String>,
+}
+
+impl<T: PreferencesType> PreferencesChanged<T> {
+    pub(crate) fn new(old: T, new: T) -> Self {
+        let changed_fields = changed_fields(&old, &new);
+        Self {
+            old,
+            new,
+            changed_fields,
+        }
+    }
+
+    /// Returns true if the field in `path` changed, either because it changed itself,
+    /// or because any of its children or parents changed.
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_simple_preferences::*;
+    /// # #[derive(Reflect, Default)]
+    /// # struct GraphicsPreferences {
+    /// #    present_mode: u32,
+    /// # }
+    /// fn recreate_swapchain(mut changes: EventReader<PreferencesChanged<GraphicsPreferences>>) {
+    ///     for change in changes.read() {
+    ///         if change.is_field_changed("present_mode") {
+    ///             // ...
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn is_field_changed(&self, path: &str) -> bool {
+        self.changed_fields
+            .iter()
+            .any(|changed_field| is_same_or_child_path(changed_field, path))
+    }
+}
+
+impl<T: PreferencesType> Clone for PreferencesChanged<T> {
+    fn clone(&self) -> Self {
+        Self {
+            old: crate::clone_preferences(&self.old),
+            new: crate::clone_preferences(&self.new),
+            changed_fields: self.changed_fields.clone(),
+        }
+    }
+}
+
+/// Run condition that returns true if the field in `path` of the preferences of type `T` changed.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_simple_preferences::*;
+/// # #[derive(Reflect, Default)]
+/// # struct GraphicsPreferences {
+/// #    present_mode: u32,
+/// # }
+/// # fn recreate_swapchain() {}
+/// App::new()
+///     .register_preferences::<GraphicsPreferences>()
+///     .add_systems(
+///         Update,
+///         recreate_swapchain.run_if(preferences_field_changed::<GraphicsPreferences>("present_mode")),
+///     );
+/// ```
+pub fn preferences_field_changed<T: PreferencesType>(
+    path: &'static str,
+) -> impl FnMut(EventReader<PreferencesChanged<T>>) -> bool + Clone {
+    move |mut changes: EventReader<PreferencesChanged<T>>| {
+        // Every event needs to be read, so they are not read again next time
+        changes
+            .read()
+            .filter(|change| change.is_field_changed(path))
+            .count()
+            > 0
+    }
+}
+
+// Returns true if one path is the same as the other, or one of them is a child of the other
+fn is_same_or_child_path(a: &str, b: &str) -> bool {
+    fn is_child_path(parent: &str, child: &str) -> bool {
+        parent.is_empty()
+            || child
+                .strip_prefix(parent)
+                .is_some_and(|rest| rest.starts_with(['.', '[']))
+    }
+
+    a == b || is_child_path(a, b) || is_child_path(b, a)
+}
+
+/// Returns the paths of the fields that are different between `old` and `new`, using
+/// the same syntax as [`bevy::reflect::GetPath`], e.g: `window.present_mode`, `0` or `list[3]`.
+///
+/// Structs, tuples and enums with the same variant are compared field by field,
+/// lists and arrays with the same length are compared element by element,
+/// any other value is compared as a whole.
+/// If the root value itself changed, the returned path is empty.
+pub fn changed_fields(old: &dyn PartialReflect, new: &dyn PartialReflect) -> Vec<String> {
+    let mut paths = Vec::new();
+    collect_changed_fields(old, new, "", &mut paths);
+    paths
+}
+
+pub(crate) fn field_path(path: &str, field: impl std::fmt::Display) -> String {
+    if path.is_empty() {
+        field.to_string()
+    } else {
+        format!("{path}.{field}")
+    }
+}
+
+fn collect_changed_fields(
+    old: &dyn PartialReflect,
+    new: &dyn PartialReflect,
+    path: &str,
+    paths: &mut Vec<String>,
+) {
+    let old_type = old.get_represented_type_info().map(|info| info.type_id());
+    let new_type = new.get_represented_type_info().map(|info| info.type_id());
+
+    if old_type != new_type {
+        paths.push(path.to_owned());
+        return;
+    }
+
+    match (old.reflect_ref(), new.reflect_ref()) {
+        (ReflectRef::Struct(old), ReflectRef::Struct(new)) => {
+            for (index, old_field) in old.iter_fields().enumerate() {
+                let name = old.name_at(index).expect("Field index out of bounds");
+                let field_path = field_path(path, name);
+                match new.field(name) {
+                    Some(new_field) => {
+                        collect_changed_fields(old_field, new_field, &field_path, paths)
+                    }
+                    None => paths.push(field_path),
+                }
+            }
+        }
+        (ReflectRef::TupleStruct(old), ReflectRef::TupleStruct(new))
+            if old.field_len() == new.field_len() =>
+        {
+            for (index, (old_field, new_field)) in
+                old.iter_fields().zip(new.iter_fields()).enumerate()
+            {
+                collect_changed_fields(old_field, new_field, &field_path(path, index), paths);
+            }
+        }
+        (ReflectRef::Tuple(old), ReflectRef::Tuple(new)) if old.field_len() == new.field_len() => {
+            for (index, (old_field, new_field)) in
+                old.iter_fields().zip(new.iter_fields()).enumerate()
+            {
+                collect_changed_fields(old_field, new_field, &field_path(path, index), paths);
+            }
+        }
+        (ReflectRef::List(old), ReflectRef::List(new)) if old.len() == new.len() => {
+            for (index, (old_item, new_item)) in old.iter().zip(new.iter()).enumerate() {
+                collect_changed_fields(old_item, new_item, &format!("{path}[{index}]"), paths);
+            }
+        }
+        (ReflectRef::Array(old), ReflectRef::Array(new)) if old.len() == new.len() => {
+            for (index, (old_item, new_item)) in old.iter().zip(new.iter()).enumerate() {
+                collect_changed_fields(old_item, new_item, &format!("{path}[{index}]"), paths);
+            }
+        }
+        (ReflectRef::Enum(old), ReflectRef::Enum(new))
+            if old.variant_name() == new.variant_name() =>
+        {
+            match old.variant_type() {
+                VariantType::Struct => {
+                    for index in 0..old.field_len() {
+                        let name = old.name_at(index).expect("Field index out of bounds");
+                        let field_path = field_path(path, name);
+                        match (old.field(name), new.field(name)) {
+                            (Some(old_field), Some(new_field)) => {
+                                collect_changed_fields(old_field, new_field, &field_path, paths)
+                            }
+                            _ => paths.push(field_path),
+                        }
+                    }
+                }
+                VariantType::Tuple => {
+                    for index in 0..old.field_len() {
+                        match (old.field_at(index), new.field_at(index)) {
+                            (Some(old_field), Some(new_field)) => collect_changed_fields(
+                                old_field,
+                                new_field,
+                                &field_path(path, index),
+                                paths,
+                            ),
+                            _ => paths.push(field_path(path, index)),
+                        }
+                    }
+                }
+                VariantType::Unit => {}
+            }
+        }
+        _ => {
+            if !old.reflect_partial_eq(new).unwrap_or(false) {
+                paths.push(path.to_owned());
+            }
+        }
+    }
+}
+
+/// Last value of the preferences of type `T` that has been committed.
+/// Used to detect if the preferences have actually changed.
+#[derive(Resource, Deref)]
+pub(crate) struct PreferencesSnapshot<T: PreferencesType>(T);
+
+impl<T: PreferencesType> PreferencesSnapshot<T> {
+    pub(crate) fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    pub(crate) fn replace(&mut self, value: T) -> T {
+        std::mem::replace(&mut self.0, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{changed_fields, is_same_or_child_path};
+    use bevy::prelude::*;
+
+    #[derive(Reflect, Clone, Default)]
+    enum Mode {
+        #[default]
+        Windowed,
+        Fullscreen {
+            monitor: u32,
+        },
+    }
+
+    #[derive(Reflect, Clone, Default)]
+    struct Window {
+        mode: Mode,
+        size: (u32, u32),
+    }
+
+    #[derive(Reflect, Clone, Default)]
+    struct Graphics {
+        window: Window,
+        fov: f32,
+        layers: Vec<u32>,
+    }
+
+    #[test]
+    fn test_changed_fields_unchanged() {
+        let graphics = Graphics::default();
+        assert!(changed_fields(&graphics, &graphics.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_changed_fields_nested() {
+        let old = Graphics {
+            window: Window {
+                mode: Mode::Fullscreen { monitor: 0 },
+                size: (800, 600),
+            },
+            fov: 90.0,
+            layers: vec![1, 2, 3],
+        };
+        let mut new = old.clone();
+        new.window.mode = Mode::Fullscreen { monitor: 1 };
+        new.window.size.1 = 768;
+        new.layers[2] = 4;
+
+        assert_eq!(
+            changed_fields(&old, &new),
+            ["window.mode.monitor", "window.size.1", "layers[2]"]
+        );
+    }
+
+    #[test]
+    fn test_changed_fields_different_variant_and_length() {
+        let old = Graphics::default();
+        let mut new = old.clone();
+        new.window.mode = Mode::Fullscreen { monitor: 0 };
+        new.layers.push(1);
+
+        assert_eq!(changed_fields(&old, &new), ["window.mode", "layers"]);
+    }
+
+    #[test]
+    fn test_changed_fields_root_value() {
+        assert_eq!(changed_fields(&1u32, &2u32), [""]);
+    }
+
+    #[test]
+    fn test_is_same_or_child_path() {
+        assert!(is_same_or_child_path("window.mode", "window.mode"));
+        assert!(is_same_or_child_path("window.mode.monitor", "window"));
+        assert!(is_same_or_child_path("window", "window.mode.monitor"));
+        assert!(is_same_or_child_path("layers[2]", "layers"));
+        assert!(is_same_or_child_path("", "fov"));
+        assert!(!is_same_or_child_path("window.mode", "window.size"));
+        assert!(!is_same_or_child_path("fov", "fov_scale"));
+    }
+}
@@ -0,0 +1,88 @@
+use crate::PreferencesType;
+use crate::defaults::PreferencesWorldExt;
+use crate::history::PreferencesHistory;
+use crate::resource::PreferencesResource;
+use bevy::prelude::*;
+use std::any::TypeId;
+
+/// Extension for [`Commands`] that allows to modify preferences.
+pub trait PreferencesCommandsExt {
+    /// Undoes the last change of the preferences of type `T`.
+    /// Requires [`crate::PreferencesHistoryPlugin`] to be added for `T`.
+    fn undo_preferences<T: PreferencesType>(&mut self);
+
+    /// Redoes the last undone change of the preferences of type `T`.
+    /// Requires [`crate::PreferencesHistoryPlugin`] to be added for `T`.
+    fn redo_preferences<T: PreferencesType>(&mut self);
+
+    /// Resets the preferences of type `T` back to their default value.
+    fn reset_preferences<T: PreferencesType>(&mut self);
+
+    /// Resets the field in `path` of the preferences of type `T` back to its default value.
+    /// Logs an error if the path doesn't exist.
+    fn reset_preferences_field<T: PreferencesType>(&mut self, path: impl Into<String>);
+
+    /// Resets every registered preferences back to their default value.
+    fn reset_all_preferences(&mut self);
+}
+
+impl PreferencesCommandsExt for Commands<'_, '_> {
+    fn undo_preferences<T: PreferencesType>(&mut self) {
+        self.queue(|world: &mut World| {
+            with_history::<T>(world, |history, preferences| {
+                history.undo(preferences);
+            });
+        });
+    }
+
+    fn redo_preferences<T: PreferencesType>(&mut self) {
+        self.queue(|world: &mut World| {
+            with_history::<T>(world, |history, preferences| {
+                history.redo(preferences);
+            });
+        });
+    }
+
+    fn reset_preferences<T: PreferencesType>(&mut self) {
+        self.queue(|world: &mut World| {
+            world.reset_preferences_by_type_id(TypeId::of::<T>());
+        });
+    }
+
+    fn reset_preferences_field<T: PreferencesType>(&mut self, path: impl Into<String>) {
+        let path = path.into();
+        self.queue(move |world: &mut World| {
+            if let Err(err) = world.reset_preferences_field_by_type_id(TypeId::of::<T>(), &path) {
+                error!(
+                    "Error resetting field of preferences {}: {err}",
+                    T::short_type_path()
+                );
+            }
+        });
+    }
+
+    fn reset_all_preferences(&mut self) {
+        self.queue(|world: &mut World| {
+            world.reset_all_preferences();
+        });
+    }
+}
+
+fn with_history<T: PreferencesType>(
+    world: &mut World,
+    f: impl FnOnce(&mut PreferencesHistory<T>, &mut T),
+) {
+    if !world.contains_resource::<PreferencesHistory<T>>() {
+        warn!(
+            "History is not enabled for preferences {}",
+            T::short_type_path()
+        );
+        return;
+    }
+
+    world.resource_scope(|world, mut history: Mut<PreferencesHistory<T>>| {
+        if let Some(mut preferences) = world.get_resource_mut::<PreferencesResource<T>>() {
+            f(&mut history, &mut preferences);
+        }
+    });
+}
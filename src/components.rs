@@ -0,0 +1,244 @@
+use crate::registry::RegisterMapTypesExt;
+use crate::{
+    Preferences, PreferencesProfileSwitched, PreferencesResource, PreferencesSet, PreferencesType,
+    RegisterPreferencesExt, clone_preferences,
+};
+use bevy::prelude::*;
+use bevy::reflect::Reflectable;
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+
+/// Identifier of an entity that needs to be stable across runs, used to persist its components.
+/// See [`RegisterPreferencesExt::persist_components`].
+#[derive(Component, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PersistId(pub String);
+
+/// Preferences with the persisted components of type `C`, by the [`PersistId`] of their entity.
+/// See [`RegisterPreferencesExt::persist_components`].
+#[derive(Reflect, Clone, Debug, PartialEq)]
+pub struct PersistedComponents<C: PreferencesType> {
+    /// Last value of the component on every entity.
+    pub entities: BTreeMap<String, C>,
+}
+
+impl<C: PreferencesType> Default for PersistedComponents<C> {
+    fn default() -> Self {
+        Self {
+            entities: BTreeMap::new(),
+        }
+    }
+}
+
+/// Marks entities whose component `C` has already been restored.
+#[derive(Component)]
+struct PersistedComponentRestored<C>(PhantomData<C>);
+
+impl<C> Default for PersistedComponentRestored<C> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+pub(crate) struct PersistComponentsPlugin<C>(PhantomData<C>);
+
+impl<C> Default for PersistComponentsPlugin<C> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<C> Plugin for PersistComponentsPlugin<C>
+where
+    C: Component + Reflectable + PreferencesType,
+{
+    fn build(&self, app: &mut App) {
+        app.register_map_types::<String, C>()
+            .register_preferences::<PersistedComponents<C>>()
+            .add_observer(restore_spawned_component::<C>)
+            .add_systems(
+                PreUpdate,
+                (
+                    restore_components::<C>,
+                    restore_switched_components::<C>
+                        .after(PreferencesSet::AssignResources)
+                        .run_if(on_event::<PreferencesProfileSwitched>),
+                ),
+            )
+            .add_systems(PostUpdate, track_components::<C>);
+    }
+}
+
+fn restore_component<C: Component + Reflectable + PreferencesType>(
+    commands: &mut Commands,
+    entity: Entity,
+    persist_id: &PersistId,
+    preferences: &PersistedComponents<C>,
+) {
+    let mut entity_commands = commands.entity(entity);
+    entity_commands.insert(PersistedComponentRestored::<C>::default());
+
+    if let Some(component) = preferences.entities.get(&persist_id.0) {
+        debug!(
+            "Restoring {} of entity {}",
+            C::short_type_path(),
+            persist_id.0
+        );
+        entity_commands.insert(clone_preferences(component));
+    }
+}
+
+// Restores the component as soon as the entity is spawned, once the preferences are loaded
+fn restore_spawned_component<C: Component + Reflectable + PreferencesType>(
+    trigger: Trigger<OnAdd, PersistId>,
+    mut commands: Commands,
+    preferences: Option<Res<PreferencesResource<PersistedComponents<C>>>>,
+    entities: Query<&PersistId>,
+) {
+    if let (Some(preferences), Ok(persist_id)) = (preferences, entities.get(trigger.entity())) {
+        restore_component(&mut commands, trigger.entity(), persist_id, &preferences);
+    }
+}
+
+// Restores the component of entities spawned before the preferences were loaded
+fn restore_components<C: Component + Reflectable + PreferencesType>(
+    mut commands: Commands,
+    preferences: Res<PreferencesResource<PersistedComponents<C>>>,
+    entities: Query<(Entity, &PersistId), Without<PersistedComponentRestored<C>>>,
+) {
+    for (entity, persist_id) in &entities {
+        restore_component(&mut commands, entity, persist_id, &preferences);
+    }
+}
+
+// Restores the component of every entity again with the values of the new profile
+fn restore_switched_components<C: Component + Reflectable + PreferencesType>(
+    mut commands: Commands,
+    preferences: Res<PreferencesResource<PersistedComponents<C>>>,
+    entities: Query<(Entity, &PersistId)>,
+) {
+    for (entity, persist_id) in &entities {
+        restore_component(&mut commands, entity, persist_id, &preferences);
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn track_components<C: Component + Reflectable + PreferencesType>(
+    mut preferences: Preferences<PersistedComponents<C>>,
+    entities: Query<(&PersistId, &C), (Changed<C>, With<PersistedComponentRestored<C>>)>,
+) {
+    for (persist_id, component) in &entities {
+        let is_unchanged = preferences
+            .entities
+            .get(&persist_id.0)
+            .and_then(|stored| stored.reflect_partial_eq(component))
+            .unwrap_or(false);
+
+        if !is_unchanged {
+            preferences
+                .entities
+                .insert(persist_id.0.clone(), clone_preferences(component));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::serializable_map::PreferencesSerializableMap;
+    use crate::{
+        PersistId, PersistedComponents, PreferencesProfileSwitched, PreferencesResource,
+        RegisterPreferencesExt,
+    };
+    use bevy::prelude::*;
+
+    #[derive(Component, Reflect, Clone, Debug, Default, PartialEq)]
+    struct Zoom(f32);
+
+    fn new_app(stored: &[(&str, Zoom)]) -> App {
+        let mut app = App::new();
+        app.persist_components::<Zoom>()
+            .insert_resource(PreferencesResource::new(PersistedComponents {
+                entities: stored
+                    .iter()
+                    .map(|(id, zoom)| (id.to_string(), zoom.clone()))
+                    .collect(),
+            }));
+        app.update();
+        app
+    }
+
+    fn stored_zoom<'a>(app: &'a App, id: &str) -> Option<&'a Zoom> {
+        app.world()
+            .resource::<PreferencesResource<PersistedComponents<Zoom>>>()
+            .entities
+            .get(id)
+    }
+
+    #[test]
+    fn test_components_are_restored_on_spawn() {
+        let mut app = new_app(&[("editor_camera", Zoom(2.0))]);
+
+        let camera = app
+            .world_mut()
+            .spawn((PersistId("editor_camera".into()), Zoom::default()))
+            .id();
+        let other = app
+            .world_mut()
+            .spawn((PersistId("other".into()), Zoom(3.0)))
+            .id();
+
+        assert_eq!(app.world().get::<Zoom>(camera), Some(&Zoom(2.0)));
+        assert_eq!(app.world().get::<Zoom>(other), Some(&Zoom(3.0)));
+
+        app.update();
+        assert_eq!(stored_zoom(&app, "editor_camera"), Some(&Zoom(2.0)));
+        assert_eq!(stored_zoom(&app, "other"), Some(&Zoom(3.0)));
+    }
+
+    #[test]
+    fn test_components_are_restored_after_switching_profiles() {
+        let mut app = new_app(&[("editor_camera", Zoom(2.0))]);
+        let camera = app
+            .world_mut()
+            .spawn((PersistId("editor_camera".into()), Zoom::default()))
+            .id();
+        app.update();
+
+        let mut storage_map = {
+            let type_registry_arc = app.world().resource::<AppTypeRegistry>().0.clone();
+            PreferencesSerializableMap::empty(type_registry_arc)
+        };
+        storage_map.set(PersistedComponents {
+            entities: [("editor_camera".to_string(), Zoom(5.0))].into(),
+        });
+        app.insert_resource(storage_map);
+        app.world_mut().send_event(PreferencesProfileSwitched {
+            previous: None,
+            active: Some("Player 2".into()),
+        });
+        app.update();
+
+        assert_eq!(app.world().get::<Zoom>(camera), Some(&Zoom(5.0)));
+        assert_eq!(stored_zoom(&app, "editor_camera"), Some(&Zoom(5.0)));
+    }
+
+    #[test]
+    fn test_changed_components_are_stored() {
+        let mut app = new_app(&[]);
+
+        let camera = app
+            .world_mut()
+            .spawn((PersistId("editor_camera".into()), Zoom(1.0)))
+            .id();
+        app.world_mut().spawn(Zoom(5.0));
+        app.update();
+
+        app.world_mut().get_mut::<Zoom>(camera).unwrap().0 = 4.0;
+        app.update();
+
+        let preferences = app
+            .world()
+            .resource::<PreferencesResource<PersistedComponents<Zoom>>>();
+        assert_eq!(preferences.entities.len(), 1);
+        assert_eq!(stored_zoom(&app, "editor_camera"), Some(&Zoom(4.0)));
+    }
+}
@@ -0,0 +1,454 @@
+use crate::{PreferencesError, PreferencesType, ReflectPreferences, Result};
+use bevy::prelude::*;
+use bevy::reflect::{ApplyError, ReflectMut, ReflectPath, ReflectRef};
+use bevy::utils::{HashMap, HashSet};
+use std::any::TypeId;
+
+/// Stores the default value of every registered preferences type.
+///
+/// Values are registered using [`crate::RegisterPreferencesExt`], and they can be
+/// used to reset preferences back to their defaults. See [`PreferencesWorldExt`].
+///
+/// Defaults overridden by the final app using
+/// [`crate::RegisterPreferencesExt::override_preferences_default`] take precedence over
+/// the registered ones.
+#[derive(Resource, Default)]
+pub struct PreferencesDefaults {
+    values: HashMap<TypeId, Box<dyn Reflect>>,
+    overridden: HashSet<TypeId>,
+}
+
+impl PreferencesDefaults {
+    /// Returns the default value of the preferences of type `T`.
+    pub fn get<T: PreferencesType>(&self) -> Option<&T> {
+        self.values
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref())
+    }
+
+    /// Returns the default value of the preferences represented by `type_id`.
+    pub fn get_by_type_id(&self, type_id: TypeId) -> Option<&dyn Reflect> {
+        self.values.get(&type_id).map(|value| &**value)
+    }
+
+    /// Iterator over the default values of every registered preferences type.
+    pub fn iter(&self) -> impl Iterator<Item = (TypeId, &dyn Reflect)> {
+        self.values
+            .iter()
+            .map(|(type_id, value)| (*type_id, &**value))
+    }
+
+    /// Returns true if the default value of the preferences of type `T` has been overridden.
+    pub fn is_overridden<T: PreferencesType>(&self) -> bool {
+        self.overridden.contains(&TypeId::of::<T>())
+    }
+
+    /// Inserts the registered default value, unless it has been overridden.
+    pub(crate) fn insert<T: PreferencesType>(&mut self, value: T) {
+        if !self.is_overridden::<T>() {
+            self.values.insert(TypeId::of::<T>(), Box::new(value));
+        }
+    }
+
+    pub(crate) fn insert_override<T: PreferencesType>(&mut self, value: T) {
+        self.overridden.insert(TypeId::of::<T>());
+        self.values.insert(TypeId::of::<T>(), Box::new(value));
+    }
+}
+
+/// Returns true if the field in `path` is different in `value` and `default_value`.
+/// Invalid paths are considered different.
+pub(crate) fn differs_from_default(
+    value: &dyn PartialReflect,
+    default_value: &dyn PartialReflect,
+    path: &str,
+) -> bool {
+    match (
+        path.reflect_element(value),
+        path.reflect_element(default_value),
+    ) {
+        (Ok(field), Ok(default_field)) => !field.reflect_partial_eq(default_field).unwrap_or(false),
+        _ => true,
+    }
+}
+
+/// Resets the field in `path` of `value` to the one in `default_value`.
+pub(crate) fn reset_field(
+    value: &mut dyn PartialReflect,
+    default_value: &dyn PartialReflect,
+    path: &str,
+) -> Result<()> {
+    let invalid_path =
+        |err: &dyn std::fmt::Display| PreferencesError::InvalidFieldPath(format!("{path}: {err}"));
+
+    let default_field = path
+        .reflect_element(default_value)
+        .map_err(|err| invalid_path(&err))?;
+    let field = path
+        .reflect_element_mut(value)
+        .map_err(|err| invalid_path(&err))?;
+
+    replace_value(field, default_field).map_err(|err| invalid_path(&err))
+}
+
+/// Replaces `value` with `new_value`.
+///
+/// Unlike [`PartialReflect::try_apply`], the elements of lists, maps and sets
+/// that are not in `new_value` are removed.
+pub(crate) fn replace_value(
+    value: &mut dyn PartialReflect,
+    new_value: &dyn PartialReflect,
+) -> std::result::Result<(), ApplyError> {
+    match value.reflect_mut() {
+        ReflectMut::Struct(value) => {
+            let ReflectRef::Struct(new_value) = new_value.reflect_ref() else {
+                return value.try_apply(new_value);
+            };
+            for (index, new_field) in new_value.iter_fields().enumerate() {
+                let name = new_value.name_at(index).unwrap_or_default();
+                if let Some(field) = value.field_mut(name) {
+                    replace_value(field, new_field)?;
+                }
+            }
+        }
+        ReflectMut::TupleStruct(value) => {
+            let ReflectRef::TupleStruct(new_value) = new_value.reflect_ref() else {
+                return value.try_apply(new_value);
+            };
+            for (index, new_field) in new_value.iter_fields().enumerate() {
+                if let Some(field) = value.field_mut(index) {
+                    replace_value(field, new_field)?;
+                }
+            }
+        }
+        ReflectMut::Tuple(value) => {
+            let ReflectRef::Tuple(new_value) = new_value.reflect_ref() else {
+                return value.try_apply(new_value);
+            };
+            for (index, new_field) in new_value.iter_fields().enumerate() {
+                if let Some(field) = value.field_mut(index) {
+                    replace_value(field, new_field)?;
+                }
+            }
+        }
+        ReflectMut::Array(value) => {
+            let ReflectRef::Array(new_value) = new_value.reflect_ref() else {
+                return value.try_apply(new_value);
+            };
+            for (index, new_item) in new_value.iter().enumerate() {
+                if let Some(item) = value.get_mut(index) {
+                    replace_value(item, new_item)?;
+                }
+            }
+        }
+        ReflectMut::List(value) => {
+            let ReflectRef::List(new_value) = new_value.reflect_ref() else {
+                return value.try_apply(new_value);
+            };
+            while value.len() > new_value.len() {
+                value.pop();
+            }
+            for (index, new_item) in new_value.iter().enumerate() {
+                match value.get_mut(index) {
+                    Some(item) => replace_value(item, new_item)?,
+                    None => value.push(new_item.clone_value()),
+                }
+            }
+        }
+        ReflectMut::Map(value) => {
+            let ReflectRef::Map(new_value) = new_value.reflect_ref() else {
+                return value.try_apply(new_value);
+            };
+            let removed: Vec<_> = value
+                .iter()
+                .filter(|(key, _)| new_value.get(*key).is_none())
+                .map(|(key, _)| key.clone_value())
+                .collect();
+            for key in removed {
+                value.remove(&*key);
+            }
+            for (key, new_item) in new_value.iter() {
+                match value.get_mut(key) {
+                    Some(item) => replace_value(item, new_item)?,
+                    None => {
+                        value.insert_boxed(key.clone_value(), new_item.clone_value());
+                    }
+                }
+            }
+        }
+        ReflectMut::Set(value) => {
+            let ReflectRef::Set(new_value) = new_value.reflect_ref() else {
+                return value.try_apply(new_value);
+            };
+            let removed: Vec<_> = value
+                .iter()
+                .filter(|item| !new_value.contains(*item))
+                .map(|item| item.clone_value())
+                .collect();
+            for item in removed {
+                value.remove(&*item);
+            }
+            value.try_apply(new_value.as_partial_reflect())?;
+        }
+        ReflectMut::Enum(value) => {
+            let ReflectRef::Enum(new_value) = new_value.reflect_ref() else {
+                return value.try_apply(new_value);
+            };
+            if value.variant_name() != new_value.variant_name() {
+                return value.try_apply(new_value.as_partial_reflect());
+            }
+            for index in 0..new_value.field_len() {
+                if let (Some(field), Some(new_field)) =
+                    (value.field_at_mut(index), new_value.field_at(index))
+                {
+                    replace_value(field, new_field)?;
+                }
+            }
+        }
+        ReflectMut::Opaque(value) => value.try_apply(new_value)?,
+    }
+    Ok(())
+}
+
+/// Extension for [`World`] that allows to query and reset preferences without knowing their type.
+pub trait PreferencesWorldExt {
+    /// Resets the preferences represented by `type_id` back to their default value.
+    fn reset_preferences_by_type_id(&mut self, type_id: TypeId);
+
+    /// Resets the field in `path` of the preferences represented by `type_id` back to its default value.
+    fn reset_preferences_field_by_type_id(&mut self, type_id: TypeId, path: &str) -> Result<()>;
+
+    /// Resets every registered preferences back to their default value.
+    fn reset_all_preferences(&mut self);
+
+    /// Returns true if the preferences represented by `type_id` are equal to their default value.
+    /// Returns `None` if they are not registered.
+    fn preferences_is_default_by_type_id(&self, type_id: TypeId) -> Option<bool>;
+
+    /// Returns true if the field in `path` of the preferences represented by `type_id` is
+    /// different from its default value. Returns `None` if they are not registered.
+    fn preferences_differs_from_default_by_type_id(
+        &self,
+        type_id: TypeId,
+        path: &str,
+    ) -> Option<bool>;
+}
+
+fn reflect_preferences(world: &World, type_id: TypeId) -> Option<ReflectPreferences> {
+    let type_registry = world.get_resource::<AppTypeRegistry>()?.read();
+    type_registry
+        .get_type_data::<ReflectPreferences>(type_id)
+        .cloned()
+}
+
+fn default_value(world: &World, type_id: TypeId) -> Option<Box<dyn PartialReflect>> {
+    world
+        .get_resource::<PreferencesDefaults>()?
+        .get_by_type_id(type_id)
+        .map(|value| value.clone_value())
+}
+
+impl PreferencesWorldExt for World {
+    fn reset_preferences_by_type_id(&mut self, type_id: TypeId) {
+        let (Some(reflect_preferences), Some(default_value)) = (
+            reflect_preferences(self, type_id),
+            default_value(self, type_id),
+        ) else {
+            return;
+        };
+
+        if let Some(mut value) = reflect_preferences.get_mut(self) {
+            if let Err(err) = replace_value(value.as_partial_reflect_mut(), &*default_value) {
+                error!("Error resetting preferences: {err}");
+            }
+        }
+    }
+
+    fn reset_preferences_field_by_type_id(&mut self, type_id: TypeId, path: &str) -> Result<()> {
+        let (Some(reflect_preferences), Some(default_value)) = (
+            reflect_preferences(self, type_id),
+            default_value(self, type_id),
+        ) else {
+            return Ok(());
+        };
+
+        match reflect_preferences.get_mut(self) {
+            Some(mut value) => reset_field(value.as_partial_reflect_mut(), &*default_value, path),
+            None => Ok(()),
+        }
+    }
+
+    fn reset_all_preferences(&mut self) {
+        let Some(defaults) = self.get_resource::<PreferencesDefaults>() else {
+            return;
+        };
+        let type_ids: Vec<_> = defaults.iter().map(|(type_id, _)| type_id).collect();
+        for type_id in type_ids {
+            self.reset_preferences_by_type_id(type_id);
+        }
+    }
+
+    fn preferences_is_default_by_type_id(&self, type_id: TypeId) -> Option<bool> {
+        let value = reflect_preferences(self, type_id)?.get(self)?;
+        let default_value = self
+            .get_resource::<PreferencesDefaults>()?
+            .get_by_type_id(type_id)?;
+        Some(
+            value
+                .reflect_partial_eq(default_value.as_partial_reflect())
+                .unwrap_or(false),
+        )
+    }
+
+    fn preferences_differs_from_default_by_type_id(
+        &self,
+        type_id: TypeId,
+        path: &str,
+    ) -> Option<bool> {
+        let value = reflect_preferences(self, type_id)?.get(self)?;
+        let default_value = self
+            .get_resource::<PreferencesDefaults>()?
+            .get_by_type_id(type_id)?;
+        Some(differs_from_default(
+            value.as_partial_reflect(),
+            default_value.as_partial_reflect(),
+            path,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Preferences, PreferencesCommandsExt, PreferencesError, PreferencesRef, PreferencesResource,
+        PreferencesWorldExt, RegisterPreferencesExt,
+    };
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::prelude::*;
+    use std::any::TypeId;
+    use std::collections::BTreeMap;
+
+    #[derive(Reflect, Default)]
+    struct Audio {
+        volume: u32,
+        muted: bool,
+    }
+
+    #[derive(Reflect, Default)]
+    struct Inventory {
+        items: Vec<String>,
+        counts: BTreeMap<String, u32>,
+    }
+
+    #[derive(Reflect)]
+    struct Window {
+        size: (u32, u32),
+    }
+
+    fn new_app() -> App {
+        let mut app = App::new();
+        app.register_preferences::<Audio>()
+            .register_preferences_with_default_value(Window { size: (800, 600) })
+            .insert_resource(PreferencesResource::new(Audio {
+                volume: 5,
+                muted: true,
+            }))
+            .insert_resource(PreferencesResource::new(Window { size: (1024, 600) }));
+        app.update();
+        app
+    }
+
+    #[test]
+    fn test_is_default_and_differs_from_default() {
+        let mut app = new_app();
+        app.world_mut()
+            .run_system_once(
+                |audio: PreferencesRef<Audio>, window: Preferences<Window>| {
+                    assert!(!audio.is_default());
+                    assert!(audio.differs_from_default("volume"));
+                    assert!(!window.differs_from_default("size.1"));
+                    assert!(window.differs_from_default("size.0"));
+                    assert_eq!(window.default_value().size, (800, 600));
+                },
+            )
+            .unwrap();
+
+        let world = app.world();
+        assert_eq!(
+            world.preferences_is_default_by_type_id(TypeId::of::<Audio>()),
+            Some(false)
+        );
+        assert_eq!(
+            world.preferences_differs_from_default_by_type_id(TypeId::of::<Window>(), "size.1"),
+            Some(false)
+        );
+        assert_eq!(
+            world.preferences_is_default_by_type_id(TypeId::of::<u32>()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_reset_and_reset_field() {
+        let mut app = new_app();
+        app.world_mut()
+            .run_system_once(
+                |mut audio: Preferences<Audio>, mut window: Preferences<Window>| {
+                    audio.reset_field("muted").unwrap();
+                    assert_eq!(audio.volume, 5);
+                    assert!(!audio.muted);
+                    assert!(matches!(
+                        audio.reset_field("pitch"),
+                        Err(PreferencesError::InvalidFieldPath(_))
+                    ));
+
+                    window.reset();
+                    assert!(window.is_default());
+                },
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_reset_through_commands() {
+        let mut app = new_app();
+
+        app.world_mut()
+            .commands()
+            .reset_preferences_field::<Window>("size.0");
+        app.world_mut().flush();
+        assert_eq!(
+            app.world().resource::<PreferencesResource<Window>>().size,
+            (800, 600)
+        );
+
+        app.world_mut().commands().reset_all_preferences();
+        app.world_mut().flush();
+        let audio = app.world().resource::<PreferencesResource<Audio>>();
+        assert_eq!(audio.volume, 0);
+        assert!(!audio.muted);
+    }
+
+    #[test]
+    fn test_reset_removes_elements_not_in_default() {
+        let mut app = new_app();
+        app.register_preferences::<Inventory>()
+            .insert_resource(PreferencesResource::new(Inventory {
+                items: vec!["sword".into(), "shield".into()],
+                counts: BTreeMap::from([("arrows".into(), 10)]),
+            }));
+
+        app.world_mut()
+            .run_system_once(|mut inventory: Preferences<Inventory>| {
+                inventory.reset_field("items").unwrap();
+                assert!(inventory.items.is_empty());
+                assert_eq!(inventory.counts.len(), 1);
+            })
+            .unwrap();
+
+        app.world_mut().reset_all_preferences();
+        let inventory = app.world().resource::<PreferencesResource<Inventory>>();
+        assert!(inventory.items.is_empty());
+        assert!(inventory.counts.is_empty());
+    }
+}
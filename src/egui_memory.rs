@@ -0,0 +1,232 @@
+use crate::PreferencesSet;
+use crate::storage::{PreferencesStorage, PreferencesStorageResource};
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::{EguiContexts, EguiPlugin, EguiPreUpdateSet};
+use bevy_inspector_egui::egui;
+use std::time::Duration;
+
+const EGUI_MEMORY_KEY: &str = "egui_memory.ron";
+const SAVE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Plugin that persists the egui memory of the primary window, like window positions or collapsing state.
+///
+/// The memory is stored as side data next to the preferences, see [`PreferencesStorage::load_side_data`],
+/// so it never modifies the preferences themselves.
+/// It's restored before the first egui pass, and only saved when it actually changed.
+///
+/// Requires the `egui` feature, and on Linux the `bevy/x11` or `bevy/wayland` feature.
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_simple_preferences::*;
+/// App::new()
+///     .add_plugins(DefaultPlugins)
+///     .add_plugins(PreferencesPlugin::persisted_with_app_name("MyApp"))
+///     .add_plugins(PreferencesEguiMemoryPlugin);
+/// ```
+#[derive(Default)]
+pub struct PreferencesEguiMemoryPlugin;
+
+impl Plugin for PreferencesEguiMemoryPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugins(EguiPlugin);
+        }
+        app.init_resource::<EguiMemoryState>()
+            .add_systems(
+                PreUpdate,
+                restore_egui_memory
+                    .after(EguiPreUpdateSet::InitContexts)
+                    .before(EguiPreUpdateSet::BeginPass)
+                    .run_if(resource_exists::<PreferencesStorageResource>),
+            )
+            .add_systems(
+                Last,
+                save_egui_memory
+                    .before(PreferencesSet::Save)
+                    .run_if(resource_exists::<PreferencesStorageResource>),
+            );
+    }
+}
+
+#[derive(Resource, Default)]
+struct EguiMemoryState {
+    restored: bool,
+    saved: Option<String>,
+    last_save_time: Duration,
+}
+
+fn restore_egui_memory(
+    mut egui_contexts: EguiContexts,
+    storage: Res<PreferencesStorageResource>,
+    mut state: ResMut<EguiMemoryState>,
+) {
+    if state.restored {
+        return;
+    }
+    if let Some(ctx) = egui_contexts.try_ctx_mut() {
+        restore_memory(ctx, &**storage, &mut state);
+    }
+}
+
+fn save_egui_memory(
+    time: Res<Time<Real>>,
+    mut egui_contexts: EguiContexts,
+    storage: Res<PreferencesStorageResource>,
+    mut state: ResMut<EguiMemoryState>,
+    mut app_exit: EventReader<AppExit>,
+) {
+    let is_exiting = !app_exit.is_empty();
+    app_exit.clear();
+
+    // Never overwrite the stored memory before restoring it
+    if !state.restored {
+        return;
+    }
+    if !is_exiting && time.elapsed() - state.last_save_time < SAVE_INTERVAL {
+        return;
+    }
+    if let Some(ctx) = egui_contexts.try_ctx_mut() {
+        state.last_save_time = time.elapsed();
+        save_memory(ctx, &**storage, &mut state);
+    }
+}
+
+fn restore_memory(
+    ctx: &egui::Context,
+    storage: &dyn PreferencesStorage,
+    state: &mut EguiMemoryState,
+) {
+    state.restored = true;
+
+    let data = match storage.load_side_data(EGUI_MEMORY_KEY) {
+        Ok(Some(data)) => data,
+        Ok(None) => return,
+        Err(err) => {
+            error!("Error loading egui memory: {err}");
+            return;
+        }
+    };
+
+    match ron::from_str::<egui::Memory>(&data) {
+        Ok(memory) => {
+            ctx.memory_mut(move |current| *current = memory);
+            state.saved = Some(data);
+        }
+        Err(err) => error!("Error deserializing egui memory: {err}"),
+    }
+}
+
+fn save_memory(ctx: &egui::Context, storage: &dyn PreferencesStorage, state: &mut EguiMemoryState) {
+    let data = match ctx.memory(ron::to_string) {
+        Ok(data) => data,
+        Err(err) => {
+            error!("Error serializing egui memory: {err}");
+            return;
+        }
+    };
+
+    if state.saved.as_ref() == Some(&data) {
+        return;
+    }
+
+    match storage.save_side_data(EGUI_MEMORY_KEY, &data) {
+        Ok(()) => state.saved = Some(data),
+        Err(err) => error!("Error saving egui memory: {err}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EGUI_MEMORY_KEY, EguiMemoryState, restore_memory, save_memory};
+    use crate::Result;
+    use crate::serializable_map::{PreferencesSerializableMap, PreferencesSerializableMapSeed};
+    use crate::storage::PreferencesStorage;
+    use bevy::reflect::TypeRegistryArc;
+    use bevy::utils::HashMap;
+    use bevy_inspector_egui::egui;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct SideDataStorage {
+        side_data: Mutex<HashMap<String, String>>,
+        saves: Mutex<usize>,
+    }
+
+    impl PreferencesStorage for SideDataStorage {
+        fn load_preferences(
+            &self,
+            _deserialize_seed: PreferencesSerializableMapSeed,
+        ) -> Result<PreferencesSerializableMap> {
+            Ok(PreferencesSerializableMap::empty(TypeRegistryArc::default()))
+        }
+
+        fn save_preferences(&self, _map: &PreferencesSerializableMap) -> Result<()> {
+            Ok(())
+        }
+
+        fn load_side_data(&self, key: &str) -> Result<Option<String>> {
+            Ok(self.side_data.lock().unwrap().get(key).cloned())
+        }
+
+        fn save_side_data(&self, key: &str, data: &str) -> Result<()> {
+            *self.saves.lock().unwrap() += 1;
+            self.side_data
+                .lock()
+                .unwrap()
+                .insert(key.to_string(), data.to_string());
+            Ok(())
+        }
+    }
+
+    fn set_value(ctx: &egui::Context, value: u32) {
+        ctx.data_mut(|data| data.insert_persisted(egui::Id::new("value"), value));
+    }
+
+    fn get_value(ctx: &egui::Context) -> Option<u32> {
+        ctx.data_mut(|data| data.get_persisted(egui::Id::new("value")))
+    }
+
+    #[test]
+    fn test_memory_is_only_saved_when_changed() {
+        let storage = SideDataStorage::default();
+        let mut state = EguiMemoryState::default();
+        let ctx = egui::Context::default();
+
+        restore_memory(&ctx, &storage, &mut state);
+        set_value(&ctx, 3);
+
+        save_memory(&ctx, &storage, &mut state);
+        save_memory(&ctx, &storage, &mut state);
+        assert_eq!(*storage.saves.lock().unwrap(), 1);
+
+        set_value(&ctx, 4);
+        save_memory(&ctx, &storage, &mut state);
+        assert_eq!(*storage.saves.lock().unwrap(), 2);
+        assert!(
+            storage
+                .side_data
+                .lock()
+                .unwrap()
+                .contains_key(EGUI_MEMORY_KEY)
+        );
+    }
+
+    #[test]
+    fn test_memory_is_restored() {
+        let storage = SideDataStorage::default();
+
+        let ctx = egui::Context::default();
+        set_value(&ctx, 7);
+        save_memory(&ctx, &storage, &mut EguiMemoryState::default());
+
+        let ctx = egui::Context::default();
+        let mut state = EguiMemoryState::default();
+        restore_memory(&ctx, &storage, &mut state);
+
+        assert_eq!(get_value(&ctx), Some(7));
+
+        // Restored memory is not saved again
+        save_memory(&ctx, &storage, &mut state);
+        assert_eq!(*storage.saves.lock().unwrap(), 1);
+    }
+}
@@ -0,0 +1,245 @@
+use crate::validation::PreferencesRange;
+use crate::{PreferencesResource, RegisterPreferencesExt};
+use bevy::prelude::*;
+use bevy::window::{PresentMode, PrimaryWindow};
+
+/// Plugin that registers [`GraphicsPreferences`] and applies them to the primary [`Window`] and to every [`Camera`].
+/// Only [`GraphicsPreferences::present_mode`] and [`GraphicsPreferences::msaa`] are applied,
+/// the other fields are stored for the game to apply.
+///
+/// Selecting a [`GraphicsPreset`] sets the fields it covers, and changing any of those fields
+/// afterward switches the preset to [`GraphicsPreset::Custom`].
+///
+/// Requires the `graphics` feature.
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_simple_preferences::*;
+/// App::new()
+///     .add_plugins(DefaultPlugins)
+///     .add_plugins(PreferencesPlugin::persisted_with_app_name("MyApp"))
+///     .add_plugins(PreferencesGraphicsPlugin)
+///     .add_systems(Update, |mut graphics: Preferences<GraphicsPreferences>| {
+///         graphics.preset = GraphicsPreset::Low;
+///     });
+/// ```
+#[derive(Default)]
+pub struct PreferencesGraphicsPlugin;
+
+impl Plugin for PreferencesGraphicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_preferences::<GraphicsPreferences>()
+            .add_systems(
+                PostUpdate,
+                (
+                    sync_graphics_preset,
+                    (apply_graphics_to_windows, apply_graphics_to_cameras),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Named set of values for the fields of [`GraphicsPreferences`] covered by presets,
+/// which is [`GraphicsPreferences::msaa`].
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum GraphicsPreset {
+    /// No anti-aliasing.
+    Low,
+    /// 2x MSAA.
+    Medium,
+    /// 4x MSAA.
+    #[default]
+    High,
+    /// Values that don't match any preset.
+    Custom,
+}
+
+/// Standard graphics preferences. See [`PreferencesGraphicsPlugin`].
+#[derive(Reflect, Clone, Debug, PartialEq)]
+pub struct GraphicsPreferences {
+    /// Current preset. Setting it to a value other than [`GraphicsPreset::Custom`] overwrites the fields it covers.
+    pub preset: GraphicsPreset,
+    /// Present mode of the primary window, that controls vsync.
+    pub present_mode: PresentMode,
+    /// Anti-aliasing of every camera.
+    pub msaa: Msaa,
+    /// Scale of the rendering resolution relative to the window.
+    /// Bevy has no built-in render scale, so it's not applied, only stored for the game to size its render targets.
+    #[reflect(@PreferencesRange::new(0.25, 2.0))]
+    pub render_scale: f32,
+    /// Maximum frames per second, `None` for unlimited.
+    /// It's not applied, since limiting the frame rate depends on how the game paces its frames.
+    pub frame_rate_limit: Option<u32>,
+}
+
+impl Default for GraphicsPreferences {
+    fn default() -> Self {
+        let mut preferences = Self {
+            preset: GraphicsPreset::default(),
+            present_mode: PresentMode::AutoVsync,
+            msaa: Msaa::default(),
+            render_scale: 1.0,
+            frame_rate_limit: None,
+        };
+        preferences.apply_preset(GraphicsPreset::default());
+        preferences
+    }
+}
+
+impl GraphicsPreferences {
+    /// Sets the preset, and the fields it covers unless it's [`GraphicsPreset::Custom`].
+    pub fn apply_preset(&mut self, preset: GraphicsPreset) {
+        self.preset = preset;
+        match preset {
+            GraphicsPreset::Low => {
+                self.msaa = Msaa::Off;
+            }
+            GraphicsPreset::Medium => {
+                self.msaa = Msaa::Sample2;
+            }
+            GraphicsPreset::High => {
+                self.msaa = Msaa::Sample4;
+            }
+            GraphicsPreset::Custom => {}
+        }
+    }
+
+    /// Returns true if the fields covered by presets have the values of [`Self::preset`].
+    /// Always true for [`GraphicsPreset::Custom`].
+    pub fn matches_preset(&self) -> bool {
+        let mut preset_values = self.clone();
+        preset_values.apply_preset(self.preset);
+        preset_values == *self
+    }
+}
+
+fn sync_graphics_preset(
+    mut graphics: ResMut<PreferencesResource<GraphicsPreferences>>,
+    mut previous_preset: Local<Option<GraphicsPreset>>,
+) {
+    let previous_preset = previous_preset.replace(graphics.preset);
+    if previous_preset.is_none() || !graphics.is_changed() {
+        return;
+    }
+
+    if previous_preset != Some(graphics.preset) && graphics.preset != GraphicsPreset::Custom {
+        if !graphics.matches_preset() {
+            let preset = graphics.preset;
+            graphics.apply_preset(preset);
+        }
+    } else if !graphics.matches_preset() {
+        graphics.preset = GraphicsPreset::Custom;
+    }
+}
+
+/// Applies the graphics preferences to a window, only modifying fields that are different.
+fn apply_graphics_to_window(graphics: &GraphicsPreferences, mut window: Mut<Window>) {
+    if window.present_mode != graphics.present_mode {
+        window.present_mode = graphics.present_mode;
+    }
+}
+
+fn apply_graphics_to_windows(
+    graphics: Res<PreferencesResource<GraphicsPreferences>>,
+    mut windows: Query<(Mut<Window>, Ref<PrimaryWindow>)>,
+) {
+    for (window, primary_window) in &mut windows {
+        if graphics.is_changed() || primary_window.is_added() {
+            apply_graphics_to_window(&graphics, window);
+        }
+    }
+}
+
+fn apply_graphics_to_cameras(
+    graphics: Res<PreferencesResource<GraphicsPreferences>>,
+    mut cameras: Query<(&mut Msaa, Ref<Camera>)>,
+) {
+    for (mut msaa, camera) in &mut cameras {
+        if graphics.is_changed() || camera.is_added() {
+            msaa.set_if_neq(graphics.msaa);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        GraphicsPreferences, GraphicsPreset, PreferencesGraphicsPlugin, PreferencesResource,
+    };
+    use bevy::prelude::*;
+    use bevy::window::{PresentMode, PrimaryWindow};
+
+    fn new_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(PreferencesGraphicsPlugin);
+        app.update();
+        app
+    }
+
+    fn graphics(app: &mut App) -> Mut<'_, PreferencesResource<GraphicsPreferences>> {
+        app.world_mut()
+            .resource_mut::<PreferencesResource<GraphicsPreferences>>()
+    }
+
+    #[test]
+    fn test_presets_and_custom() {
+        let mut app = new_app();
+
+        graphics(&mut app).preset = GraphicsPreset::Low;
+        app.update();
+        assert_eq!(graphics(&mut app).msaa, Msaa::Off);
+
+        graphics(&mut app).msaa = Msaa::Sample8;
+        app.update();
+        assert_eq!(graphics(&mut app).preset, GraphicsPreset::Custom);
+        assert_eq!(graphics(&mut app).msaa, Msaa::Sample8);
+
+        // Fields not covered by presets keep the preset
+        graphics(&mut app).apply_preset(GraphicsPreset::Medium);
+        app.update();
+        graphics(&mut app).present_mode = PresentMode::AutoNoVsync;
+        app.update();
+        assert_eq!(graphics(&mut app).preset, GraphicsPreset::Medium);
+        assert_eq!(graphics(&mut app).msaa, Msaa::Sample2);
+    }
+
+    #[test]
+    fn test_applied_to_window_and_cameras() {
+        let mut app = new_app();
+        let window = app
+            .world_mut()
+            .spawn((Window::default(), PrimaryWindow))
+            .id();
+        let camera = app.world_mut().spawn(Camera::default()).id();
+
+        graphics(&mut app).present_mode = PresentMode::AutoNoVsync;
+        graphics(&mut app).preset = GraphicsPreset::Medium;
+        app.update();
+
+        let present_mode = app.world().get::<Window>(window).unwrap().present_mode;
+        assert_eq!(present_mode, PresentMode::AutoNoVsync);
+        assert_eq!(app.world().get::<Msaa>(camera), Some(&Msaa::Sample2));
+
+        let late_camera = app.world_mut().spawn(Camera::default()).id();
+        app.update();
+        assert_eq!(app.world().get::<Msaa>(late_camera), Some(&Msaa::Sample2));
+
+        // Nothing is modified if the preferences did not change
+        app.world_mut().clear_trackers();
+        app.update();
+        assert!(
+            !app.world()
+                .entity(window)
+                .get_ref::<Window>()
+                .unwrap()
+                .is_changed()
+        );
+        assert!(
+            !app.world()
+                .entity(camera)
+                .get_ref::<Msaa>()
+                .unwrap()
+                .is_changed()
+        );
+    }
+}
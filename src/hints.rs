@@ -0,0 +1,195 @@
+use crate::registry::RegisterMapTypesExt;
+use crate::{RegisterPreferencesExt, unix_seconds};
+use bevy::prelude::*;
+use bevy::utils::SystemTime;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Plugin that registers [`HintsPreferences`], to store dismissed and snoozed hints,
+/// like "don't show this again" dialogs or first-time tutorials.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_simple_preferences::*;
+/// # use std::time::Duration;
+/// App::new()
+///     .add_plugins(PreferencesHintsPlugin)
+///     .add_systems(Update, |mut hints: Preferences<HintsPreferences>| {
+///         if hints.show_once("tutorial_jump") {
+///             info!("Press Space to jump");
+///         }
+///         if hints.should_show("rate_the_game") {
+///             hints.snooze("rate_the_game", Duration::from_secs(7 * 24 * 60 * 60));
+///         }
+///     });
+/// ```
+#[derive(Default)]
+pub struct PreferencesHintsPlugin;
+
+impl Plugin for PreferencesHintsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_map_types::<String, HintState>()
+            .register_preferences::<HintsPreferences>();
+    }
+}
+
+/// Stored state of a hint. Hints without a state are shown.
+#[derive(Reflect, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HintState {
+    /// The hint is never shown again.
+    Dismissed,
+    /// The hint is hidden until the wall-clock time, in seconds since the Unix epoch.
+    Snoozed {
+        /// Seconds since the Unix epoch after which the hint is shown again.
+        until: u64,
+    },
+}
+
+/// Preferences with the state of every dismissed or snoozed hint, by its id.
+/// See [`PreferencesHintsPlugin`].
+#[derive(Reflect, Clone, Debug, Default, PartialEq)]
+pub struct HintsPreferences {
+    /// State of every hint that is not shown.
+    pub hints: BTreeMap<String, HintState>,
+}
+
+impl HintsPreferences {
+    /// Returns true if the hint has not been dismissed, and it's not snoozed.
+    pub fn should_show(&self, id: &str) -> bool {
+        self.should_show_at(id, SystemTime::now())
+    }
+
+    /// Returns true if the hint has not been dismissed, and it's not snoozed at the specified time.
+    pub fn should_show_at(&self, id: &str, now: SystemTime) -> bool {
+        match self.hints.get(id) {
+            None => true,
+            Some(HintState::Dismissed) => false,
+            Some(HintState::Snoozed { until }) => unix_seconds(now) >= *until,
+        }
+    }
+
+    /// Returns true if the hint should be shown, and dismisses it, so it's only shown once.
+    pub fn show_once(&mut self, id: &str) -> bool {
+        let should_show = self.should_show(id);
+        if should_show {
+            self.dismiss(id);
+        }
+        should_show
+    }
+
+    /// Never shows the hint again.
+    pub fn dismiss(&mut self, id: impl Into<String>) {
+        self.hints.insert(id.into(), HintState::Dismissed);
+    }
+
+    /// Hides the hint for the specified duration of wall-clock time.
+    /// Durations too long to be represented hide the hint forever.
+    pub fn snooze(&mut self, id: impl Into<String>, duration: Duration) {
+        let until = SystemTime::now()
+            .checked_add(duration)
+            .map_or(u64::MAX, unix_seconds);
+        self.hints.insert(id.into(), HintState::Snoozed { until });
+    }
+
+    /// Shows the hint again.
+    pub fn reset(&mut self, id: &str) {
+        self.hints.remove(id);
+    }
+
+    /// Shows every hint again.
+    pub fn reset_all(&mut self) {
+        self.hints.clear();
+    }
+
+    /// Removes the snoozed hints that are shown again, so they're not stored anymore.
+    pub fn remove_expired(&mut self) {
+        let now = SystemTime::now();
+        let expired: Vec<_> = self
+            .hints
+            .iter()
+            .filter(|(_, state)| matches!(state, HintState::Snoozed { .. }))
+            .filter(|(id, _)| self.should_show_at(id, now))
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in expired {
+            self.hints.remove(&id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::serializable_map::PreferencesSerializableMap;
+    use crate::{HintState, HintsPreferences, Preferences, PreferencesHintsPlugin};
+    use bevy::prelude::*;
+    use bevy::utils::SystemTime;
+    use std::time::Duration;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    #[test]
+    fn test_dismiss_snooze_and_reset() {
+        let mut hints = HintsPreferences::default();
+        assert!(hints.should_show("tutorial"));
+
+        assert!(hints.show_once("tutorial"));
+        assert!(!hints.show_once("tutorial"));
+        assert_eq!(hints.hints["tutorial"], HintState::Dismissed);
+
+        hints.snooze("rate", 7 * DAY);
+        assert!(!hints.should_show("rate"));
+        assert!(!hints.should_show_at("rate", SystemTime::now() + 6 * DAY));
+        assert!(hints.should_show_at("rate", SystemTime::now() + 8 * DAY));
+
+        hints.reset("tutorial");
+        assert!(hints.should_show("tutorial"));
+
+        hints.dismiss("tutorial");
+        hints.reset_all();
+        assert!(hints.should_show("tutorial"));
+        assert!(hints.should_show("rate"));
+    }
+
+    #[test]
+    fn test_snoozing_forever_does_not_overflow() {
+        let mut hints = HintsPreferences::default();
+        hints.snooze("tutorial", Duration::MAX);
+        assert_eq!(
+            hints.hints["tutorial"],
+            HintState::Snoozed { until: u64::MAX }
+        );
+        assert!(!hints.should_show("tutorial"));
+    }
+
+    #[test]
+    fn test_expired_snoozes_are_removed() {
+        let mut hints = HintsPreferences::default();
+        hints.snooze("expired", Duration::ZERO);
+        hints.snooze("snoozed", DAY);
+        hints.dismiss("dismissed");
+
+        hints.remove_expired();
+        assert_eq!(
+            hints.hints.keys().collect::<Vec<_>>(),
+            ["dismissed", "snoozed"]
+        );
+    }
+
+    #[test]
+    fn test_hints_are_stored() {
+        App::new()
+            .add_plugins(PreferencesHintsPlugin)
+            .init_resource::<PreferencesSerializableMap>()
+            .add_systems(Update, |mut hints: Preferences<HintsPreferences>| {
+                hints.dismiss("tutorial");
+            })
+            .add_systems(
+                Last,
+                (|map: Res<PreferencesSerializableMap>| {
+                    let hints = map.get::<HintsPreferences>().unwrap();
+                    assert!(!hints.should_show("tutorial"));
+                })
+                .after(crate::PreferencesSet::SetReflectMapValues),
+            )
+            .run();
+    }
+}
@@ -0,0 +1,303 @@
+use crate::changes::PreferencesChanged;
+use crate::resource::PreferencesResource;
+use crate::{PreferencesSet, PreferencesType, clone_preferences};
+use bevy::prelude::*;
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+
+/// Plugin that enables undo and redo for the preferences of type `T`.
+/// Every time the preferences change, the previous value is stored in [`PreferencesHistory`],
+/// up to `capacity` values.
+///
+/// Changes can be undone by sending [`UndoPreferences`] and [`RedoPreferences`] events,
+/// or using [`crate::PreferencesCommandsExt`].
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_simple_preferences::*;
+/// # #[derive(Reflect, Default)]
+/// # struct LayoutPreferences;
+/// App::new()
+///     .register_preferences::<LayoutPreferences>()
+///     .add_plugins(PreferencesHistoryPlugin::<LayoutPreferences>::new(20))
+///     .add_systems(Update, |mut commands: Commands| {
+///         commands.undo_preferences::<LayoutPreferences>();
+///     });
+/// ```
+pub struct PreferencesHistoryPlugin<T> {
+    capacity: usize,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> PreferencesHistoryPlugin<T> {
+    /// Creates a history plugin that keeps up to `capacity` previous values.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: PreferencesType> Plugin for PreferencesHistoryPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PreferencesHistory::<T>::new(self.capacity))
+            .add_event::<UndoPreferences<T>>()
+            .add_event::<RedoPreferences<T>>()
+            .add_systems(
+                Last,
+                (
+                    apply_history_events::<T>.before(PreferencesSet::SetReflectMapValues),
+                    record_history::<T>.after(PreferencesSet::SetReflectMapValues),
+                ),
+            );
+    }
+}
+
+/// Event that undoes the last change of the preferences of type `T`.
+#[derive(Event)]
+pub struct UndoPreferences<T>(PhantomData<fn() -> T>);
+
+impl<T> Default for UndoPreferences<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+/// Event that redoes the last undone change of the preferences of type `T`.
+#[derive(Event)]
+pub struct RedoPreferences<T>(PhantomData<fn() -> T>);
+
+impl<T> Default for RedoPreferences<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+/// Bounded history of previous values of the preferences of type `T`.
+/// Added by [`PreferencesHistoryPlugin`].
+#[derive(Resource)]
+pub struct PreferencesHistory<T: PreferencesType> {
+    undo: VecDeque<T>,
+    redo: Vec<T>,
+    capacity: usize,
+    // Set by undo and redo, so the change they cause is not recorded
+    is_restoring: bool,
+}
+
+impl<T: PreferencesType> PreferencesHistory<T> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            undo: VecDeque::with_capacity(capacity),
+            redo: Vec::new(),
+            capacity,
+            is_restoring: false,
+        }
+    }
+
+    /// Returns true if there is any change that can be undone.
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Returns true if there is any undone change that can be redone.
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Number of changes that can be undone.
+    pub fn undo_len(&self) -> usize {
+        self.undo.len()
+    }
+
+    /// Number of changes that can be redone.
+    pub fn redo_len(&self) -> usize {
+        self.redo.len()
+    }
+
+    /// Removes all the stored history.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.is_restoring = false;
+    }
+
+    fn push_undo(&mut self, value: T) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.undo.len() == self.capacity {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(value);
+    }
+
+    /// Restores the previous value into `current`, returns false if there is nothing to undo.
+    pub(crate) fn undo(&mut self, current: &mut T) -> bool {
+        let Some(previous) = self.undo.pop_back() else {
+            return false;
+        };
+        let current_value = std::mem::replace(current, previous);
+        self.redo.push(current_value);
+        self.is_restoring = true;
+        true
+    }
+
+    /// Restores the next value into `current`, returns false if there is nothing to redo.
+    pub(crate) fn redo(&mut self, current: &mut T) -> bool {
+        let Some(next) = self.redo.pop() else {
+            return false;
+        };
+        let current_value = std::mem::replace(current, next);
+        self.push_undo(current_value);
+        self.is_restoring = true;
+        true
+    }
+}
+
+fn apply_history_events<T: PreferencesType>(
+    mut undo_events: EventReader<UndoPreferences<T>>,
+    mut redo_events: EventReader<RedoPreferences<T>>,
+    mut history: ResMut<PreferencesHistory<T>>,
+    mut preferences: ResMut<PreferencesResource<T>>,
+) {
+    for _ in undo_events.read() {
+        history.undo(&mut preferences);
+    }
+    for _ in redo_events.read() {
+        history.redo(&mut preferences);
+    }
+}
+
+fn record_history<T: PreferencesType>(
+    mut changes: EventReader<PreferencesChanged<T>>,
+    mut history: ResMut<PreferencesHistory<T>>,
+) {
+    // Changes caused by undo or redo are not recorded
+    let is_restoring = std::mem::take(&mut history.is_restoring);
+    if is_restoring {
+        changes.clear();
+        return;
+    }
+
+    for change in changes.read() {
+        history.push_undo(clone_preferences(&change.old));
+        history.redo.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Preferences, PreferencesCommandsExt, PreferencesHistory, PreferencesHistoryPlugin,
+        RegisterPreferencesExt, UndoPreferences,
+    };
+    use bevy::prelude::*;
+
+    #[derive(Reflect, Default)]
+    struct MyPreferences {
+        value: u32,
+    }
+
+    #[derive(Resource, Default)]
+    struct NextValue(Option<u32>);
+
+    fn new_app() -> App {
+        let mut app = App::new();
+        app.register_preferences::<MyPreferences>()
+            .add_plugins(PreferencesHistoryPlugin::<MyPreferences>::new(2))
+            .init_resource::<NextValue>()
+            .add_systems(
+                Update,
+                |mut next_value: ResMut<NextValue>, mut pref: Preferences<MyPreferences>| {
+                    if let Some(value) = next_value.0.take() {
+                        pref.value = value;
+                    }
+                },
+            );
+        app.update();
+        app
+    }
+
+    fn set_value(app: &mut App, value: u32) {
+        app.world_mut().resource_mut::<NextValue>().0 = Some(value);
+        app.update();
+    }
+
+    fn current_value(app: &App) -> u32 {
+        app.world()
+            .resource::<crate::PreferencesResource<MyPreferences>>()
+            .value
+    }
+
+    #[test]
+    fn test_undo_and_redo() {
+        let mut app = new_app();
+        set_value(&mut app, 1);
+        set_value(&mut app, 2);
+
+        app.world_mut()
+            .send_event(UndoPreferences::<MyPreferences>::default());
+        app.update();
+        assert_eq!(current_value(&app), 1);
+
+        app.world_mut()
+            .commands()
+            .undo_preferences::<MyPreferences>();
+        app.update();
+        assert_eq!(current_value(&app), 0);
+
+        app.world_mut()
+            .commands()
+            .redo_preferences::<MyPreferences>();
+        app.update();
+        assert_eq!(current_value(&app), 1);
+
+        let history = app.world().resource::<PreferencesHistory<MyPreferences>>();
+        assert_eq!(history.undo_len(), 1);
+        assert_eq!(history.redo_len(), 1);
+    }
+
+    #[test]
+    fn test_history_is_bounded_and_new_changes_clear_redo() {
+        let mut app = new_app();
+        set_value(&mut app, 1);
+        set_value(&mut app, 2);
+        set_value(&mut app, 3);
+
+        let history = app.world().resource::<PreferencesHistory<MyPreferences>>();
+        assert_eq!(history.undo_len(), 2);
+
+        app.world_mut()
+            .commands()
+            .undo_preferences::<MyPreferences>();
+        app.update();
+        assert_eq!(current_value(&app), 2);
+
+        set_value(&mut app, 5);
+        let history = app.world().resource::<PreferencesHistory<MyPreferences>>();
+        assert!(!history.can_redo());
+        assert_eq!(history.undo_len(), 2);
+    }
+
+    #[test]
+    fn test_clearing_history_records_pending_restore() {
+        let mut app = new_app();
+        set_value(&mut app, 1);
+        set_value(&mut app, 2);
+
+        app.world_mut().resource_scope(
+            |world, mut history: Mut<PreferencesHistory<MyPreferences>>| {
+                let mut preferences =
+                    world.resource_mut::<crate::PreferencesResource<MyPreferences>>();
+                assert!(history.undo(&mut preferences));
+                history.clear();
+            },
+        );
+        app.update();
+
+        assert_eq!(current_value(&app), 1);
+        let history = app.world().resource::<PreferencesHistory<MyPreferences>>();
+        assert_eq!(history.undo_len(), 1);
+        assert!(!history.can_redo());
+    }
+}
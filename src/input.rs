@@ -0,0 +1,646 @@
+use crate::registry::RegisterMapTypesExt;
+use crate::{Preferences, PreferencesError, PreferencesRef, RegisterPreferencesExt, Result};
+use bevy::ecs::system::SystemParam;
+use bevy::input::gamepad::{Gamepad, GamepadButton};
+use bevy::prelude::*;
+use bevy::reflect::{DynamicEnum, DynamicVariant, ReflectRef, TypeInfo, Typed, VariantInfo};
+use serde::de::Visitor;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Modifier keys of an [`InputChord`]. Each one matches both its left and right key.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct InputModifiers {
+    /// `Ctrl`, either [`KeyCode::ControlLeft`] or [`KeyCode::ControlRight`].
+    pub ctrl: bool,
+    /// `Shift`, either [`KeyCode::ShiftLeft`] or [`KeyCode::ShiftRight`].
+    pub shift: bool,
+    /// `Alt`, either [`KeyCode::AltLeft`] or [`KeyCode::AltRight`].
+    pub alt: bool,
+    /// `Super`, either [`KeyCode::SuperLeft`] or [`KeyCode::SuperRight`].
+    pub super_key: bool,
+}
+
+impl InputModifiers {
+    const NAMES: [&str; 4] = ["Ctrl", "Shift", "Alt", "Super"];
+    const KEYS: [(KeyCode, KeyCode); 4] = [
+        (KeyCode::ControlLeft, KeyCode::ControlRight),
+        (KeyCode::ShiftLeft, KeyCode::ShiftRight),
+        (KeyCode::AltLeft, KeyCode::AltRight),
+        (KeyCode::SuperLeft, KeyCode::SuperRight),
+    ];
+
+    fn flags(&self) -> [bool; 4] {
+        [self.ctrl, self.shift, self.alt, self.super_key]
+    }
+
+    fn flag_mut(&mut self, name: &str) -> Option<&mut bool> {
+        match name.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" => Some(&mut self.ctrl),
+            "shift" => Some(&mut self.shift),
+            "alt" | "option" => Some(&mut self.alt),
+            "super" | "cmd" | "meta" => Some(&mut self.super_key),
+            _ => None,
+        }
+    }
+
+    /// Returns true if every modifier is pressed, and no other one is,
+    /// so `S` is not pressed while holding `Ctrl`.
+    pub fn pressed(&self, keys: &ButtonInput<KeyCode>) -> bool {
+        self.pressed_with_button(keys, None)
+    }
+
+    // The button of a chord can be a modifier key itself, like `ShiftLeft`, that is not an extra modifier
+    fn pressed_with_button(&self, keys: &ButtonInput<KeyCode>, button: Option<KeyCode>) -> bool {
+        self.flags()
+            .into_iter()
+            .zip(Self::KEYS)
+            .all(|(flag, (left, right))| {
+                button.is_some_and(|button| button == left || button == right)
+                    || flag == keys.any_pressed([left, right])
+            })
+    }
+}
+
+/// Button that triggers an [`InputChord`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InputButton {
+    /// A keyboard key, like `S` or `Space`.
+    Key(KeyCode),
+    /// A mouse button, like `MouseLeft`.
+    Mouse(MouseButton),
+    /// A gamepad button, like `GamepadSouth`.
+    Gamepad(GamepadButton),
+}
+
+/// Button pressed while holding some modifiers, that is bound to an action in [`InputBindings`].
+///
+/// It's stored in a human-friendly form, like `"Ctrl+S"`, `"MouseLeft"` or `"GamepadSouth"`,
+/// using [`FromStr`] and [`fmt::Display`].
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_simple_preferences::*;
+/// let chord: InputChord = "Ctrl+Shift+S".parse().unwrap();
+/// assert_eq!(chord, InputChord::from(KeyCode::KeyS).with_ctrl().with_shift());
+/// assert_eq!(chord.to_string(), "Ctrl+Shift+S");
+/// ```
+#[derive(Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[reflect(opaque)]
+#[reflect(Debug, PartialEq, Hash, Serialize, Deserialize)]
+pub struct InputChord {
+    /// Modifiers that need to be held.
+    pub modifiers: InputModifiers,
+    /// Button that triggers the chord.
+    pub button: InputButton,
+}
+
+impl InputChord {
+    /// Creates a chord without modifiers.
+    pub fn new(button: InputButton) -> Self {
+        Self {
+            modifiers: InputModifiers::default(),
+            button,
+        }
+    }
+
+    /// Adds the `Ctrl` modifier.
+    pub fn with_ctrl(mut self) -> Self {
+        self.modifiers.ctrl = true;
+        self
+    }
+
+    /// Adds the `Shift` modifier.
+    pub fn with_shift(mut self) -> Self {
+        self.modifiers.shift = true;
+        self
+    }
+
+    /// Adds the `Alt` modifier.
+    pub fn with_alt(mut self) -> Self {
+        self.modifiers.alt = true;
+        self
+    }
+
+    /// Adds the `Super` modifier.
+    pub fn with_super(mut self) -> Self {
+        self.modifiers.super_key = true;
+        self
+    }
+
+    fn modifiers_pressed(&self, keys: &ButtonInput<KeyCode>) -> bool {
+        let button = match self.button {
+            InputButton::Key(key) => Some(key),
+            _ => None,
+        };
+        self.modifiers.pressed_with_button(keys, button)
+    }
+
+    /// Returns true if the button and every modifier are pressed, without any other modifier.
+    pub fn pressed(
+        &self,
+        keys: &ButtonInput<KeyCode>,
+        mouse_buttons: &ButtonInput<MouseButton>,
+        gamepad_buttons: &ButtonInput<GamepadButton>,
+    ) -> bool {
+        self.modifiers_pressed(keys)
+            && match self.button {
+                InputButton::Key(key) => keys.pressed(key),
+                InputButton::Mouse(button) => mouse_buttons.pressed(button),
+                InputButton::Gamepad(button) => gamepad_buttons.pressed(button),
+            }
+    }
+
+    /// Returns true if the button has just been pressed while holding every modifier,
+    /// without any other modifier.
+    pub fn just_pressed(
+        &self,
+        keys: &ButtonInput<KeyCode>,
+        mouse_buttons: &ButtonInput<MouseButton>,
+        gamepad_buttons: &ButtonInput<GamepadButton>,
+    ) -> bool {
+        self.modifiers_pressed(keys)
+            && match self.button {
+                InputButton::Key(key) => keys.just_pressed(key),
+                InputButton::Mouse(button) => mouse_buttons.just_pressed(button),
+                InputButton::Gamepad(button) => gamepad_buttons.just_pressed(button),
+            }
+    }
+}
+
+impl From<KeyCode> for InputChord {
+    fn from(key: KeyCode) -> Self {
+        Self::new(InputButton::Key(key))
+    }
+}
+
+impl From<MouseButton> for InputChord {
+    fn from(button: MouseButton) -> Self {
+        Self::new(InputButton::Mouse(button))
+    }
+}
+
+impl From<GamepadButton> for InputChord {
+    fn from(button: GamepadButton) -> Self {
+        Self::new(InputButton::Gamepad(button))
+    }
+}
+
+/// Returns the name of a unit variant, or `None` for other variants.
+fn unit_variant_name(value: &dyn Reflect) -> Option<&str> {
+    match value.reflect_ref() {
+        ReflectRef::Enum(value) if value.field_len() == 0 => Some(value.variant_name()),
+        _ => None,
+    }
+}
+
+/// Finds the unit variant of `T` whose name, with `prefix` and `alias`, matches `name` ignoring case.
+fn parse_unit_variant<T: FromReflect + Typed>(
+    name: &str,
+    alias: impl Fn(&str) -> Option<String>,
+) -> Option<T> {
+    let TypeInfo::Enum(enum_info) = T::type_info() else {
+        return None;
+    };
+    let variant = enum_info.iter().find_map(|variant| match variant {
+        VariantInfo::Unit(variant)
+            if variant.name().eq_ignore_ascii_case(name)
+                || alias(variant.name()).is_some_and(|alias| alias.eq_ignore_ascii_case(name)) =>
+        {
+            Some(variant.name())
+        }
+        _ => None,
+    })?;
+    T::from_reflect(&DynamicEnum::new(variant, DynamicVariant::Unit))
+}
+
+// `KeyS` is displayed as `S`, and `Digit1` as `1`
+fn key_alias(variant_name: &str) -> Option<String> {
+    ["Key", "Digit"]
+        .into_iter()
+        .filter_map(|prefix| variant_name.strip_prefix(prefix))
+        .find(|rest| rest.len() == 1)
+        .map(str::to_string)
+}
+
+fn parse_button(name: &str) -> Option<InputButton> {
+    if let Some(button) = name.strip_prefix("Mouse") {
+        return match button.parse() {
+            Ok(other) => Some(MouseButton::Other(other)),
+            Err(_) => parse_unit_variant(button, |_| None),
+        }
+        .map(InputButton::Mouse);
+    }
+    if let Some(button) = name.strip_prefix("Gamepad") {
+        return match button.strip_prefix("Other").map(str::parse) {
+            Some(Ok(other)) => Some(GamepadButton::Other(other)),
+            _ => parse_unit_variant(button, |_| None),
+        }
+        .map(InputButton::Gamepad);
+    }
+    parse_unit_variant(name, key_alias).map(InputButton::Key)
+}
+
+impl fmt::Display for InputButton {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Key(key) => {
+                let name = unit_variant_name(key).unwrap_or("Unidentified");
+                match key_alias(name) {
+                    Some(alias) => f.write_str(&alias),
+                    None => f.write_str(name),
+                }
+            }
+            Self::Mouse(MouseButton::Other(other)) => write!(f, "Mouse{other}"),
+            Self::Mouse(button) => write!(f, "Mouse{button:?}"),
+            Self::Gamepad(GamepadButton::Other(other)) => write!(f, "GamepadOther{other}"),
+            Self::Gamepad(button) => write!(f, "Gamepad{button:?}"),
+        }
+    }
+}
+
+impl fmt::Display for InputChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (flag, name) in self
+            .modifiers
+            .flags()
+            .into_iter()
+            .zip(InputModifiers::NAMES)
+        {
+            if flag {
+                write!(f, "{name}+")?;
+            }
+        }
+        write!(f, "{}", self.button)
+    }
+}
+
+impl FromStr for InputChord {
+    type Err = PreferencesError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || PreferencesError::InvalidInputChord(s.to_string());
+
+        let mut parts: Vec<_> = s.split('+').map(str::trim).collect();
+        let button = parts.pop().ok_or_else(invalid)?;
+
+        let mut modifiers = InputModifiers::default();
+        for modifier in parts {
+            *modifiers.flag_mut(modifier).ok_or_else(invalid)? = true;
+        }
+
+        Ok(Self {
+            modifiers,
+            button: parse_button(button).ok_or_else(invalid)?,
+        })
+    }
+}
+
+impl Serialize for InputChord {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for InputChord {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct InputChordVisitor;
+
+        impl Visitor<'_> for InputChordVisitor {
+            type Value = InputChord;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an input chord like \"Ctrl+S\"")
+            }
+
+            fn visit_str<E: serde::de::Error>(
+                self,
+                v: &str,
+            ) -> std::result::Result<Self::Value, E> {
+                v.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(InputChordVisitor)
+    }
+}
+
+/// Chord bound to more than one action. See [`InputBindings::conflicts`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InputBindingConflict {
+    /// Chord that is bound more than once.
+    pub chord: InputChord,
+    /// Actions the chord is bound to.
+    pub actions: Vec<String>,
+}
+
+/// Plugin that registers [`InputBindings`] with the default bindings of the game.
+///
+/// Actions are queried using [`ActionInput`], and the bindings are stored in a human-friendly form,
+/// like `"Ctrl+S"`. See [`InputChord`].
+///
+/// Requires the `input` feature.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy::input::gamepad::GamepadButton;
+/// # use bevy_simple_preferences::*;
+/// App::new()
+///     .add_plugins(PreferencesInputPlugin::new(
+///         InputBindings::default()
+///             .with_action("save", [InputChord::from(KeyCode::KeyS).with_ctrl()])
+///             .with_action("jump", [KeyCode::Space.into(), GamepadButton::South.into()]),
+///     ))
+///     .add_systems(Update, |input: ActionInput| {
+///         if input.just_pressed("save") {
+///             info!("Saving");
+///         }
+///     });
+/// ```
+pub struct PreferencesInputPlugin {
+    default_bindings: InputBindings,
+}
+
+impl PreferencesInputPlugin {
+    /// Creates the plugin with the bindings used until the user changes them.
+    pub fn new(default_bindings: InputBindings) -> Self {
+        Self { default_bindings }
+    }
+}
+
+impl Plugin for PreferencesInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_map_types::<String, Vec<InputChord>>()
+            .register_preferences_with_default_value(self.default_bindings.clone());
+    }
+}
+
+/// Preferences that bind named actions to one or more [`InputChord`]. See [`PreferencesInputPlugin`].
+#[derive(Reflect, Clone, Debug, Default, PartialEq)]
+pub struct InputBindings {
+    /// Chords bound to every action.
+    pub actions: BTreeMap<String, Vec<InputChord>>,
+}
+
+impl InputBindings {
+    /// Binds the chords to an action, replacing its previous bindings.
+    pub fn with_action(
+        mut self,
+        action: impl Into<String>,
+        chords: impl IntoIterator<Item = InputChord>,
+    ) -> Self {
+        self.actions
+            .insert(action.into(), chords.into_iter().collect());
+        self
+    }
+
+    /// Returns the chords bound to an action.
+    pub fn get(&self, action: &str) -> &[InputChord] {
+        self.actions.get(action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the actions, other than `action`, that `chord` is already bound to.
+    pub fn conflicts_with(&self, action: &str, chord: &InputChord) -> Vec<&str> {
+        self.actions
+            .iter()
+            .filter(|(other, chords)| *other != action && chords.contains(chord))
+            .map(|(other, _)| other.as_str())
+            .collect()
+    }
+
+    /// Returns every chord bound to more than one action.
+    pub fn conflicts(&self) -> Vec<InputBindingConflict> {
+        let mut bound: Vec<InputBindingConflict> = Vec::new();
+        for (action, chords) in &self.actions {
+            for chord in chords {
+                match bound.iter_mut().find(|bound| bound.chord == *chord) {
+                    Some(bound) if !bound.actions.contains(action) => {
+                        bound.actions.push(action.clone());
+                    }
+                    Some(_) => {}
+                    None => bound.push(InputBindingConflict {
+                        chord: *chord,
+                        actions: vec![action.clone()],
+                    }),
+                }
+            }
+        }
+        bound.retain(|bound| bound.actions.len() > 1);
+        bound
+    }
+}
+
+impl Preferences<'_, InputBindings> {
+    /// Resets the bindings of a single action back to their default value.
+    pub fn reset_action(&mut self, action: &str) {
+        match self.default_value().actions.get(action).cloned() {
+            Some(chords) => {
+                self.actions.insert(action.to_string(), chords);
+            }
+            None => {
+                self.actions.remove(action);
+            }
+        }
+    }
+}
+
+/// System param that tells if the actions of [`InputBindings`] are pressed,
+/// checking the keyboard, the mouse and every gamepad.
+///
+/// Requires the `input` feature.
+#[derive(SystemParam)]
+pub struct ActionInput<'w, 's> {
+    bindings: PreferencesRef<'w, InputBindings>,
+    keys: Option<Res<'w, ButtonInput<KeyCode>>>,
+    mouse_buttons: Option<Res<'w, ButtonInput<MouseButton>>>,
+    gamepads: Query<'w, 's, &'static Gamepad>,
+}
+
+impl ActionInput<'_, '_> {
+    /// Returns true if any chord bound to the action is pressed.
+    pub fn pressed(&self, action: &str) -> bool {
+        self.any_chord(action, InputChord::pressed)
+    }
+
+    /// Returns true if any chord bound to the action has just been pressed.
+    pub fn just_pressed(&self, action: &str) -> bool {
+        self.any_chord(action, InputChord::just_pressed)
+    }
+
+    fn any_chord(
+        &self,
+        action: &str,
+        is_active: impl Fn(
+            &InputChord,
+            &ButtonInput<KeyCode>,
+            &ButtonInput<MouseButton>,
+            &ButtonInput<GamepadButton>,
+        ) -> bool,
+    ) -> bool {
+        let no_keys = ButtonInput::default();
+        let no_mouse_buttons = ButtonInput::default();
+        let no_gamepad_buttons = ButtonInput::default();
+        let keys = self.keys.as_deref().unwrap_or(&no_keys);
+        let mouse_buttons = self.mouse_buttons.as_deref().unwrap_or(&no_mouse_buttons);
+
+        self.bindings.get(action).iter().any(|chord| {
+            let mut gamepad_buttons = self.gamepads.iter().map(Gamepad::digital);
+            match chord.button {
+                InputButton::Gamepad(_) => gamepad_buttons
+                    .any(|gamepad_buttons| is_active(chord, keys, mouse_buttons, gamepad_buttons)),
+                _ => is_active(chord, keys, mouse_buttons, &no_gamepad_buttons),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ActionInput, InputBindingConflict, InputBindings, InputChord, Preferences,
+        PreferencesInputPlugin,
+    };
+    use bevy::input::gamepad::GamepadButton;
+    use bevy::prelude::*;
+    use bevy::reflect::serde::TypedReflectDeserializer;
+    use serde::de::DeserializeSeed;
+    use std::any::TypeId;
+
+    #[test]
+    fn test_chords_round_trip_through_strings() {
+        let chords = [
+            ("Ctrl+S", InputChord::from(KeyCode::KeyS).with_ctrl()),
+            (
+                "Shift+Alt+1",
+                InputChord::from(KeyCode::Digit1).with_shift().with_alt(),
+            ),
+            ("Super+F5", InputChord::from(KeyCode::F5).with_super()),
+            ("Space", InputChord::from(KeyCode::Space)),
+            ("MouseLeft", InputChord::from(MouseButton::Left)),
+            ("Mouse7", InputChord::from(MouseButton::Other(7))),
+            ("GamepadSouth", InputChord::from(GamepadButton::South)),
+            ("GamepadOther3", InputChord::from(GamepadButton::Other(3))),
+        ];
+
+        for (name, chord) in chords {
+            assert_eq!(name.parse::<InputChord>().unwrap(), chord);
+            assert_eq!(chord.to_string(), name);
+        }
+
+        assert_eq!(
+            "control + shift + s".parse::<InputChord>().unwrap(),
+            InputChord::from(KeyCode::KeyS).with_ctrl().with_shift()
+        );
+        assert!("Hyper+S".parse::<InputChord>().is_err());
+        assert!("Ctrl+".parse::<InputChord>().is_err());
+        assert!("Unidentified".parse::<InputChord>().is_err());
+    }
+
+    #[test]
+    fn test_bindings_are_serialized_as_strings() {
+        let bindings = InputBindings::default()
+            .with_action("save", [InputChord::from(KeyCode::KeyS).with_ctrl()]);
+
+        let mut app = App::new();
+        app.add_plugins(PreferencesInputPlugin::new(InputBindings::default()));
+        let registry = app.world().resource::<AppTypeRegistry>().read();
+        let serializer = bevy::reflect::serde::TypedReflectSerializer::new(&bindings, &registry);
+        let output = serde_json::to_string(&serializer).unwrap();
+        assert_eq!(output, r#"{"actions":{"save":["Ctrl+S"]}}"#);
+
+        let registration = registry.get(TypeId::of::<InputBindings>()).unwrap();
+        let deserializer = TypedReflectDeserializer::new(registration, &registry);
+        let value = deserializer
+            .deserialize(&mut serde_json::Deserializer::from_str(&output))
+            .unwrap();
+        assert_eq!(
+            InputBindings::from_reflect(value.as_partial_reflect()),
+            Some(bindings)
+        );
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let save = InputChord::from(KeyCode::KeyS).with_ctrl();
+        let bindings = InputBindings::default()
+            .with_action("save", [save])
+            .with_action("save_as", [save, save])
+            .with_action("jump", [KeyCode::Space.into()]);
+
+        assert_eq!(
+            bindings.conflicts(),
+            [InputBindingConflict {
+                chord: save,
+                actions: vec!["save".into(), "save_as".into()],
+            }]
+        );
+        assert_eq!(bindings.conflicts_with("jump", &save), ["save", "save_as"]);
+        assert!(
+            bindings
+                .conflicts_with("jump", &KeyCode::Space.into())
+                .is_empty()
+        );
+    }
+
+    #[derive(Resource, Default)]
+    struct Pressed(Vec<&'static str>);
+
+    #[test]
+    fn test_actions_are_pressed_and_reset() {
+        let mut app = App::new();
+        app.add_plugins(PreferencesInputPlugin::new(
+            InputBindings::default()
+                .with_action("save", [InputChord::from(KeyCode::KeyS).with_ctrl()])
+                .with_action("back", [KeyCode::KeyS.into()])
+                .with_action("sprint", [KeyCode::ShiftLeft.into()])
+                .with_action("jump", [KeyCode::Space.into(), GamepadButton::South.into()]),
+        ))
+        .init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<Pressed>()
+        .add_systems(
+            Update,
+            |input: ActionInput, mut pressed: ResMut<Pressed>| {
+                pressed.0 = ["save", "back", "sprint", "jump"]
+                    .into_iter()
+                    .filter(|action| input.pressed(action))
+                    .collect();
+            },
+        );
+
+        let pressed = |app: &mut App, keys: &[KeyCode]| {
+            let mut input = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+            input.reset_all();
+            for key in keys {
+                input.press(*key);
+            }
+            app.update();
+            app.world().resource::<Pressed>().0.clone()
+        };
+
+        assert_eq!(pressed(&mut app, &[KeyCode::KeyS]), ["back"]);
+        assert_eq!(
+            pressed(&mut app, &[KeyCode::ControlRight, KeyCode::KeyS]),
+            ["save"]
+        );
+        assert_eq!(pressed(&mut app, &[KeyCode::ShiftLeft]), ["sprint"]);
+        assert_eq!(
+            pressed(&mut app, &[KeyCode::ShiftLeft, KeyCode::KeyS]),
+            ["sprint"]
+        );
+
+        let mut gamepad = Gamepad::default();
+        gamepad.digital_mut().press(GamepadButton::South);
+        app.world_mut().spawn(gamepad);
+        assert_eq!(pressed(&mut app, &[]), ["jump"]);
+
+        app.world_mut()
+            .run_system_cached(|mut bindings: Preferences<InputBindings>| {
+                bindings
+                    .actions
+                    .insert("jump".into(), vec![KeyCode::KeyW.into()]);
+                bindings.reset_action("jump");
+                assert_eq!(bindings.get("jump").len(), 2);
+            })
+            .unwrap();
+    }
+}
@@ -0,0 +1,539 @@
+use crate::defaults::replace_value;
+use crate::schema::{PreferencesFieldSchema, PreferencesSchema, PreferencesTypeSchema, WidgetHint};
+use crate::validation::{PreferencesRange, as_f64, set_f64};
+use crate::{PreferencesDefaults, PreferencesWorldExt, ReflectPreferences};
+use bevy::ecs::system::SystemState;
+use bevy::prelude::*;
+use bevy::reflect::{
+    DynamicEnum, DynamicVariant, PartialReflect, ReflectMut, ReflectPath, TypeInfo, TypeRegistry,
+    VariantInfo,
+};
+use bevy_inspector_egui::bevy_egui::{EguiContexts, EguiPlugin};
+use bevy_inspector_egui::{DefaultInspectorConfigPlugin, egui, reflect_inspector};
+use std::any::TypeId;
+use std::collections::{BTreeMap, HashMap};
+
+const DEFAULT_CATEGORY: &str = "General";
+
+/// Plugin that adds an egui window to edit every registered preferences type.
+///
+/// Preferences are grouped by their [`crate::Category`] and can be filtered by name.
+/// Fields are edited through reflection, using the metadata in [`crate::PreferencesSchema`] where present,
+/// and every type can be reset to its defaults or reverted to the value it had when the window was opened.
+///
+/// Preferences are only marked as changed on real edits, so they are validated and stored as usual.
+///
+/// Requires the `egui` feature, and on Linux the `bevy/x11` or `bevy/wayland` feature.
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_simple_preferences::*;
+/// App::new()
+///     .add_plugins(DefaultPlugins)
+///     .add_plugins(PreferencesPlugin::persisted_with_app_name("MyApp"))
+///     .add_plugins(PreferencesInspectorPlugin)
+///     .add_systems(Update, toggle_preferences_window);
+///
+/// fn toggle_preferences_window(
+///     keys: Res<ButtonInput<KeyCode>>,
+///     mut window: ResMut<PreferencesInspectorWindow>,
+/// ) {
+///     if keys.just_pressed(KeyCode::F10) {
+///         window.open = !window.open;
+///     }
+/// }
+/// ```
+#[derive(Default)]
+pub struct PreferencesInspectorPlugin;
+
+impl Plugin for PreferencesInspectorPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<DefaultInspectorConfigPlugin>() {
+            app.add_plugins(DefaultInspectorConfigPlugin);
+        }
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugins(EguiPlugin);
+        }
+        app.init_resource::<PreferencesInspectorWindow>()
+            .add_systems(Update, preferences_inspector_window);
+    }
+}
+
+/// State of the window added by [`PreferencesInspectorPlugin`].
+#[derive(Resource)]
+pub struct PreferencesInspectorWindow {
+    /// Whether the window is open. True by default.
+    pub open: bool,
+    /// Text used to filter the preferences and fields shown.
+    pub search: String,
+    snapshots: HashMap<TypeId, Box<dyn PartialReflect>>,
+    // Resets need the type registry, so they're applied once the UI releases it
+    pending_resets: Vec<TypeId>,
+}
+
+impl Default for PreferencesInspectorWindow {
+    fn default() -> Self {
+        Self {
+            open: true,
+            search: String::new(),
+            snapshots: HashMap::new(),
+            pending_resets: Vec::new(),
+        }
+    }
+}
+
+fn preferences_inspector_window(world: &mut World, egui_contexts: &mut SystemState<EguiContexts>) {
+    let Some(ctx) = egui_contexts
+        .get_mut(world)
+        .try_ctx_mut()
+        .map(|ctx| ctx.clone())
+    else {
+        return;
+    };
+    preferences_inspector_ui(&ctx, world);
+}
+
+pub(crate) fn preferences_inspector_ui(ctx: &egui::Context, world: &mut World) {
+    world.resource_scope(|world, mut window: Mut<PreferencesInspectorWindow>| {
+        if !window.open {
+            if !window.snapshots.is_empty() {
+                window.snapshots.clear();
+            }
+            return;
+        }
+
+        let app_type_registry = world.resource::<AppTypeRegistry>().clone();
+        let type_registry = app_type_registry.read();
+        let schema = PreferencesSchema::new(&type_registry);
+        let window = window.as_mut();
+
+        let mut open = true;
+        egui::Window::new("Preferences")
+            .open(&mut open)
+            .default_size((300., 400.))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Search");
+                    ui.text_edit_singleline(&mut window.search);
+                });
+                ui.separator();
+
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for (category, types) in visible_types(&schema, &window.search) {
+                        ui.heading(category);
+                        for (type_schema, fields) in types {
+                            type_ui(ui, world, &type_registry, window, type_schema, &fields);
+                        }
+                    }
+                });
+            });
+        window.open = open;
+
+        drop(type_registry);
+        for type_id in window.pending_resets.drain(..) {
+            world.reset_preferences_by_type_id(type_id);
+        }
+    });
+}
+
+type VisibleTypes<'a> =
+    BTreeMap<&'a str, Vec<(&'a PreferencesTypeSchema, Vec<&'a PreferencesFieldSchema>)>>;
+
+/// Preferences and fields that match `search`, grouped by category.
+fn visible_types<'a>(schema: &'a PreferencesSchema, search: &str) -> VisibleTypes<'a> {
+    let search = search.to_lowercase();
+    let mut categories = VisibleTypes::new();
+
+    for type_schema in schema.iter() {
+        let fields: Vec<_> = if matches_search(type_label(type_schema), &search) {
+            type_schema.fields.iter().collect()
+        } else {
+            type_schema
+                .fields
+                .iter()
+                .filter(|field| field_matches_search(field, &search))
+                .collect()
+        };
+        if fields.is_empty() && !search.is_empty() {
+            continue;
+        }
+
+        let category = type_schema.metadata.category.unwrap_or(DEFAULT_CATEGORY);
+        categories
+            .entry(category)
+            .or_default()
+            .push((type_schema, fields));
+    }
+
+    categories
+}
+
+fn matches_search(text: &str, search: &str) -> bool {
+    text.to_lowercase().contains(search)
+}
+
+fn field_matches_search(field: &PreferencesFieldSchema, search: &str) -> bool {
+    matches_search(field_label(field), search)
+        || field
+            .fields
+            .iter()
+            .any(|field| field_matches_search(field, search))
+}
+
+fn type_label(type_schema: &PreferencesTypeSchema) -> &str {
+    type_schema
+        .metadata
+        .display_name
+        .unwrap_or(type_schema.short_type_path)
+}
+
+fn field_label(field: &PreferencesFieldSchema) -> &str {
+    field.metadata.display_name.unwrap_or(&field.name)
+}
+
+fn type_ui(
+    ui: &mut egui::Ui,
+    world: &mut World,
+    type_registry: &TypeRegistry,
+    window: &mut PreferencesInspectorWindow,
+    type_schema: &PreferencesTypeSchema,
+    fields: &[&PreferencesFieldSchema],
+) {
+    let type_id = type_schema.type_id;
+    let Some(reflect_preferences) = type_registry
+        .get_type_data::<ReflectPreferences>(type_id)
+        .cloned()
+    else {
+        return;
+    };
+
+    egui::CollapsingHeader::new(type_label(type_schema))
+        .id_salt(type_schema.type_path)
+        .default_open(true)
+        .show(ui, |ui| {
+            if let Some(description) = type_schema.metadata.description {
+                ui.label(description);
+            }
+
+            let Some(is_reverted) = fields_ui(
+                ui,
+                world,
+                type_registry,
+                window,
+                &reflect_preferences,
+                type_id,
+                fields,
+            ) else {
+                ui.label("Not loaded yet");
+                return;
+            };
+            let snapshot = &window.snapshots[&type_id];
+            let is_default = match (
+                reflect_preferences.get(world),
+                world
+                    .get_resource::<PreferencesDefaults>()
+                    .and_then(|defaults| defaults.get_by_type_id(type_id)),
+            ) {
+                (Some(value), Some(default_value)) => value
+                    .reflect_partial_eq(default_value.as_partial_reflect())
+                    .unwrap_or(false),
+                _ => true,
+            };
+
+            ui.horizontal(|ui| {
+                let reset = ui
+                    .add_enabled(!is_default, egui::Button::new("Reset"))
+                    .on_hover_text("Restore the default values");
+                if reset.clicked() {
+                    window.pending_resets.push(type_id);
+                }
+
+                let revert = ui
+                    .add_enabled(!is_reverted, egui::Button::new("Revert"))
+                    .on_hover_text("Restore the values from when the window was opened");
+                if revert.clicked() {
+                    if let Some(mut value) = reflect_preferences.get_mut(world) {
+                        if let Err(err) =
+                            replace_value(value.as_partial_reflect_mut(), snapshot.as_ref())
+                        {
+                            error!("Error reverting preferences: {err}");
+                        }
+                    }
+                }
+            });
+        });
+}
+
+/// Shows the fields of the preferences, returning if the value is equal to the snapshot.
+fn fields_ui(
+    ui: &mut egui::Ui,
+    world: &mut World,
+    type_registry: &TypeRegistry,
+    window: &mut PreferencesInspectorWindow,
+    reflect_preferences: &ReflectPreferences,
+    type_id: TypeId,
+    fields: &[&PreferencesFieldSchema],
+) -> Option<bool> {
+    let mut value = reflect_preferences.get_mut(world)?;
+
+    let snapshot = window
+        .snapshots
+        .entry(type_id)
+        .or_insert_with(|| value.clone_value());
+
+    let mut changed = false;
+    for field in fields {
+        changed |= field_ui(
+            ui,
+            value.bypass_change_detection().as_partial_reflect_mut(),
+            field,
+            type_registry,
+        );
+    }
+    if changed {
+        value.set_changed();
+    }
+
+    Some(
+        value
+            .reflect_partial_eq(snapshot.as_partial_reflect())
+            .unwrap_or(false),
+    )
+}
+
+fn field_ui(
+    ui: &mut egui::Ui,
+    root: &mut dyn PartialReflect,
+    field: &PreferencesFieldSchema,
+    type_registry: &TypeRegistry,
+) -> bool {
+    if !field.fields.is_empty() && field.metadata.widget.is_none() {
+        return egui::CollapsingHeader::new(field_label(field))
+            .id_salt(&field.path)
+            .show(ui, |ui| {
+                let mut changed = false;
+                for field in &field.fields {
+                    changed |= field_ui(ui, root, field, type_registry);
+                }
+                changed
+            })
+            .body_returned
+            .unwrap_or(false);
+    }
+
+    let Ok(value) = field.path.as_str().reflect_element_mut(root) else {
+        return false;
+    };
+
+    ui.horizontal(|ui| {
+        let label = ui.label(field_label(field));
+        if let Some(description) = field.metadata.description {
+            label.on_hover_text(description);
+        }
+
+        match (field.metadata.widget, field.metadata.range) {
+            (Some(WidgetHint::Slider), Some(range)) if as_f64(value).is_some() => {
+                slider_ui(ui, value, field, range)
+            }
+            (Some(WidgetHint::Dropdown), _) if has_unit_variants(value, field) => {
+                dropdown_ui(ui, value, field)
+            }
+            _ => {
+                let changed = reflect_inspector::ui_for_value(value, ui, type_registry);
+                if let Some(unit) = field.metadata.unit {
+                    ui.label(unit);
+                }
+                changed
+            }
+        }
+    })
+    .inner
+}
+
+fn slider_ui(
+    ui: &mut egui::Ui,
+    value: &mut dyn PartialReflect,
+    field: &PreferencesFieldSchema,
+    range: PreferencesRange,
+) -> bool {
+    let Some(mut number) = as_f64(value) else {
+        return false;
+    };
+
+    let mut slider = egui::Slider::new(&mut number, range.min..=range.max);
+    if let Some(step) = field.metadata.step {
+        slider = slider.step_by(step);
+    }
+    if let Some(unit) = field.metadata.unit {
+        slider = slider.suffix(format!(" {unit}"));
+    }
+    if value.try_downcast_ref::<f32>().is_none() && value.try_downcast_ref::<f64>().is_none() {
+        slider = slider.integer();
+    }
+
+    if ui.add(slider).changed() {
+        set_f64(value, number);
+        true
+    } else {
+        false
+    }
+}
+
+fn has_unit_variants(value: &dyn PartialReflect, field: &PreferencesFieldSchema) -> bool {
+    let Some(TypeInfo::Enum(enum_info)) = value.get_represented_type_info() else {
+        return false;
+    };
+    !field.variants.is_empty()
+        && field
+            .variants
+            .iter()
+            .all(|variant| matches!(enum_info.variant(variant), Some(VariantInfo::Unit(_))))
+}
+
+fn dropdown_ui(
+    ui: &mut egui::Ui,
+    value: &mut dyn PartialReflect,
+    field: &PreferencesFieldSchema,
+) -> bool {
+    let ReflectMut::Enum(enum_value) = value.reflect_mut() else {
+        return false;
+    };
+
+    let current = enum_value.variant_name().to_string();
+    let mut selected = current.as_str();
+    egui::ComboBox::from_id_salt(&field.path)
+        .selected_text(selected)
+        .show_ui(ui, |ui| {
+            for variant in &field.variants {
+                ui.selectable_value(&mut selected, *variant, *variant);
+            }
+        });
+
+    if selected == current {
+        return false;
+    }
+
+    let new_value = DynamicEnum::new(selected.to_string(), DynamicVariant::Unit);
+    enum_value.try_apply(&new_value).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{preferences_inspector_ui, visible_types};
+    use crate::{
+        Category, DisplayName, PreferencesInspectorPlugin, PreferencesInspectorWindow,
+        PreferencesResource, PreferencesSchema, RegisterPreferencesExt,
+    };
+    use bevy::prelude::*;
+    use bevy_inspector_egui::egui;
+    use std::any::TypeId;
+
+    #[derive(Reflect, Default)]
+    #[reflect(@Category("Sound"))]
+    struct AudioPreferences {
+        #[reflect(@DisplayName("Master volume"))]
+        volume: f32,
+        muted: bool,
+    }
+
+    #[derive(Reflect, Default)]
+    struct KeyboardPreferences {
+        layout: String,
+    }
+
+    fn new_app() -> App {
+        let mut app = App::new();
+        app.init_resource::<PreferencesInspectorWindow>()
+            .register_preferences::<AudioPreferences>()
+            .register_preferences::<KeyboardPreferences>();
+        app.update();
+        app
+    }
+
+    #[test]
+    fn test_visible_types_are_grouped_and_filtered() {
+        let app = new_app();
+        let schema = PreferencesSchema::new(&app.world().resource::<AppTypeRegistry>().read());
+
+        let visible = visible_types(&schema, "");
+        let categories: Vec<_> = visible.keys().copied().collect();
+        assert_eq!(categories, vec!["General", "Sound"]);
+
+        let visible = visible_types(&schema, "VOLUME");
+        let (audio, fields) = &visible["Sound"][0];
+        assert_eq!(audio.short_type_path, "AudioPreferences");
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0].name, "volume");
+        assert!(!visible.contains_key("General"));
+
+        let visible = visible_types(&schema, "keyboard");
+        assert_eq!(visible["General"][0].1.len(), 1);
+        assert!(!visible.contains_key("Sound"));
+    }
+
+    #[test]
+    fn test_showing_window_does_not_change_preferences() {
+        let mut app = new_app();
+        app.world_mut().clear_trackers();
+
+        let ctx = egui::Context::default();
+        for _ in 0..2 {
+            let _ = ctx.run(egui::RawInput::default(), |ctx| {
+                preferences_inspector_ui(ctx, app.world_mut());
+            });
+        }
+
+        assert!(
+            !app.world()
+                .resource_ref::<PreferencesResource<AudioPreferences>>()
+                .is_changed()
+        );
+        assert!(
+            !app.world()
+                .resource_ref::<PreferencesResource<KeyboardPreferences>>()
+                .is_changed()
+        );
+    }
+
+    #[test]
+    fn test_resets_are_applied_after_showing_window() {
+        let mut app = new_app();
+        app.world_mut()
+            .resource_mut::<PreferencesResource<AudioPreferences>>()
+            .volume = 0.5;
+        app.world_mut()
+            .resource_mut::<PreferencesInspectorWindow>()
+            .pending_resets
+            .push(TypeId::of::<AudioPreferences>());
+
+        let ctx = egui::Context::default();
+        let _ = ctx.run(egui::RawInput::default(), |ctx| {
+            preferences_inspector_ui(ctx, app.world_mut());
+        });
+
+        let audio = app
+            .world()
+            .resource::<PreferencesResource<AudioPreferences>>();
+        assert_eq!(audio.volume, 0.0);
+        let window = app.world().resource::<PreferencesInspectorWindow>();
+        assert!(window.pending_resets.is_empty());
+    }
+
+    #[test]
+    fn test_plugin_can_be_added_to_an_app_with_plugins() {
+        let mut app = App::new();
+        // `DefaultInspectorConfigPlugin` expects the types registered by `DefaultPlugins`
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Shader>()
+            .register_type::<Entity>()
+            .register_type::<Handle<Mesh>>()
+            .register_type::<Handle<Image>>()
+            .register_type::<bevy::render::view::RenderLayers>()
+            .add_plugins(PreferencesInspectorPlugin);
+        app.finish();
+        app.cleanup();
+        assert!(
+            app.world()
+                .contains_resource::<PreferencesInspectorWindow>()
+        );
+    }
+}
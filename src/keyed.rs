@@ -0,0 +1,274 @@
+use crate::registry::RegisterMapTypesExt;
+use crate::resource::PreferencesResource;
+use crate::{
+    Preferences, PreferencesSet, PreferencesType, RegisterPreferencesExt, clone_preferences,
+};
+use bevy::ecs::component::Tick;
+use bevy::ecs::system::{SystemChangeTick, SystemParam};
+use bevy::prelude::*;
+use bevy::reflect::Reflectable;
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+use std::sync::Mutex;
+
+/// Preferences that store a value of type `T` for every key of type `K`,
+/// like per-gamepad or per-monitor settings, as a single entry in storage.
+/// See [`KeyedPreferences`].
+///
+/// Keys are stored as map keys, so they need to serialize as strings in formats like `toml`.
+#[derive(Reflect, Clone, Debug, PartialEq)]
+pub struct KeyedPreferencesMap<K, T>
+where
+    K: Reflectable + PreferencesType + Ord,
+    T: Reflectable + PreferencesType,
+{
+    /// Value of every key that has been created.
+    pub entries: BTreeMap<K, T>,
+}
+
+impl<K, T> Default for KeyedPreferencesMap<K, T>
+where
+    K: Reflectable + PreferencesType + Ord,
+    T: Reflectable + PreferencesType,
+{
+    fn default() -> Self {
+        Self {
+            entries: BTreeMap::new(),
+        }
+    }
+}
+
+/// Value used to create the entries of [`KeyedPreferencesMap`] on demand.
+#[derive(Resource)]
+struct KeyedPreferencesEntryDefault<T>(T);
+
+/// Last tick in which every key of [`KeyedPreferencesMap`] was created, modified or removed.
+#[derive(Resource)]
+struct KeyedPreferencesTicks<K, T> {
+    snapshot: BTreeMap<K, T>,
+    ticks: BTreeMap<K, Tick>,
+}
+
+impl<K, T> Default for KeyedPreferencesTicks<K, T> {
+    fn default() -> Self {
+        Self {
+            snapshot: BTreeMap::new(),
+            ticks: BTreeMap::new(),
+        }
+    }
+}
+
+pub(crate) struct KeyedPreferencesPlugin<K, T> {
+    entry_default: Mutex<Option<T>>,
+    _key: PhantomData<fn() -> K>,
+}
+
+impl<K, T> KeyedPreferencesPlugin<K, T> {
+    pub fn new(entry_default: T) -> Self {
+        Self {
+            entry_default: Mutex::new(Some(entry_default)),
+            _key: PhantomData,
+        }
+    }
+}
+
+impl<K, T> Plugin for KeyedPreferencesPlugin<K, T>
+where
+    K: Reflectable + PreferencesType + Ord + Clone,
+    T: Reflectable + PreferencesType,
+{
+    fn build(&self, app: &mut App) {
+        let entry_default = {
+            let mut lock = self.entry_default.try_lock().unwrap();
+            lock.take().expect("Cannot build Plugin more than once")
+        };
+
+        app.register_map_types::<K, T>()
+            .register_preferences::<KeyedPreferencesMap<K, T>>()
+            .insert_resource(KeyedPreferencesEntryDefault(entry_default))
+            .init_resource::<KeyedPreferencesTicks<K, T>>()
+            .add_systems(
+                Last,
+                detect_keyed_changes::<K, T>
+                    .before(PreferencesSet::SetReflectMapValues)
+                    .run_if(
+                        resource_exists_and_changed::<
+                            PreferencesResource<KeyedPreferencesMap<K, T>>,
+                        >,
+                    ),
+            );
+    }
+}
+
+// Records the tick of every key whose value really changed
+fn detect_keyed_changes<K, T>(
+    preferences: Res<PreferencesResource<KeyedPreferencesMap<K, T>>>,
+    mut ticks: ResMut<KeyedPreferencesTicks<K, T>>,
+    system_change_tick: SystemChangeTick,
+) where
+    K: Reflectable + PreferencesType + Ord + Clone,
+    T: Reflectable + PreferencesType,
+{
+    let this_run = system_change_tick.this_run();
+    let ticks = &mut *ticks;
+
+    for (key, value) in &preferences.entries {
+        let is_unchanged = ticks
+            .snapshot
+            .get(key)
+            .and_then(|previous| previous.reflect_partial_eq(value))
+            .unwrap_or(false);
+        if !is_unchanged {
+            ticks.ticks.insert(key.clone(), this_run);
+            ticks.snapshot.insert(key.clone(), clone_preferences(value));
+        }
+    }
+
+    ticks.snapshot.retain(|key, _| {
+        let exists = preferences.entries.contains_key(key);
+        if !exists {
+            ticks.ticks.insert(key.clone(), this_run);
+        }
+        exists
+    });
+}
+
+/// System param to read and write preferences stored by key,
+/// registered with [`RegisterPreferencesExt::register_keyed_preferences`].
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_simple_preferences::*;
+/// #[derive(Reflect, Default)]
+/// struct GamepadSettings {
+///     sensitivity: f32,
+/// }
+///
+/// App::new()
+///     .register_keyed_preferences::<String, GamepadSettings>()
+///     .add_systems(Update, |mut settings: KeyedPreferences<String, GamepadSettings>| {
+///         // Created from the default value, unless it's already stored
+///         settings.entry("Xbox Controller".into()).sensitivity = 0.5;
+///     });
+/// ```
+#[derive(SystemParam)]
+pub struct KeyedPreferences<'w, K, T>
+where
+    K: Reflectable + PreferencesType + Ord + Clone,
+    T: Reflectable + PreferencesType,
+{
+    preferences: Preferences<'w, KeyedPreferencesMap<K, T>>,
+    entry_default: Res<'w, KeyedPreferencesEntryDefault<T>>,
+    ticks: Res<'w, KeyedPreferencesTicks<K, T>>,
+    system_change_tick: SystemChangeTick,
+}
+
+impl<K, T> KeyedPreferences<'_, K, T>
+where
+    K: Reflectable + PreferencesType + Ord + Clone,
+    T: Reflectable + PreferencesType,
+{
+    /// Returns the value of a key, if it has been created.
+    pub fn get(&self, key: &K) -> Option<&T> {
+        self.preferences.entries.get(key)
+    }
+
+    /// Returns the value of a key, or the default value if it has not been created.
+    pub fn get_or_default(&self, key: &K) -> &T {
+        self.get(key).unwrap_or(&self.entry_default.0)
+    }
+
+    /// Returns the value of a key mutably, creating it from the default value if needed.
+    pub fn entry(&mut self, key: K) -> &mut T {
+        let entry_default = &self.entry_default.0;
+        self.preferences
+            .entries
+            .entry(key)
+            .or_insert_with(|| clone_preferences(entry_default))
+    }
+
+    /// Removes a key, returning its value.
+    pub fn remove(&mut self, key: &K) -> Option<T> {
+        // Accessing the preferences mutably marks them as changed
+        if !self.preferences.entries.contains_key(key) {
+            return None;
+        }
+        self.preferences.entries.remove(key)
+    }
+
+    /// Iterator over every created key and its value.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &T)> {
+        self.preferences.entries.iter()
+    }
+
+    /// Returns true if the value of a key was created, modified or removed since the last time the system ran.
+    ///
+    /// Changes are detected at the end of every frame, so they're reported the following frame.
+    pub fn is_changed(&self, key: &K) -> bool {
+        self.ticks.ticks.get(key).is_some_and(|tick| {
+            tick.is_newer_than(
+                self.system_change_tick.last_run(),
+                self.system_change_tick.this_run(),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::serializable_map::PreferencesSerializableMap;
+    use crate::{KeyedPreferences, KeyedPreferencesMap, RegisterPreferencesExt};
+    use bevy::prelude::*;
+
+    #[derive(Reflect, Clone, Debug, PartialEq)]
+    struct GamepadSettings {
+        deadzone: f32,
+    }
+
+    #[derive(Resource, Default)]
+    struct Changed(Vec<(String, bool)>);
+
+    #[test]
+    fn test_entries_are_created_on_demand_and_stored() {
+        let mut app = App::new();
+        app.register_keyed_preferences_with_default_value::<String, _>(GamepadSettings {
+            deadzone: 0.1,
+        })
+        .init_resource::<PreferencesSerializableMap>()
+        .init_resource::<Changed>()
+        .add_systems(
+            Update,
+            |settings: KeyedPreferences<String, GamepadSettings>, mut changed: ResMut<Changed>| {
+                changed.0 = ["Pad A", "Pad B"]
+                    .into_iter()
+                    .map(|key| (key.to_string(), settings.is_changed(&key.to_string())))
+                    .collect();
+            },
+        );
+        app.update();
+
+        app.world_mut()
+            .run_system_cached(|mut settings: KeyedPreferences<String, GamepadSettings>| {
+                assert_eq!(settings.get(&"Pad A".into()), None);
+                assert_eq!(settings.get_or_default(&"Pad A".into()).deadzone, 0.1);
+                settings.entry("Pad A".into()).deadzone = 0.3;
+            })
+            .unwrap();
+        app.update();
+        app.update();
+
+        let changed = &app.world().resource::<Changed>().0;
+        assert_eq!(
+            changed,
+            &[("Pad A".to_string(), true), ("Pad B".to_string(), false)]
+        );
+
+        let map = app.world().resource::<PreferencesSerializableMap>();
+        let stored = map.get::<KeyedPreferencesMap<String, GamepadSettings>>();
+        assert_eq!(stored.unwrap().entries["Pad A"].deadzone, 0.3);
+
+        // Changes are only reported once
+        app.update();
+        let changed = &app.world().resource::<Changed>().0;
+        assert!(changed.iter().all(|(_, is_changed)| !is_changed));
+    }
+}
@@ -62,6 +62,9 @@
 //! # App::new().add_plugins(MyCratePlugin).run();
 //! ```
 //!
+//! Systems that only read preferences can use [`PreferencesRef`] instead, so they don't need exclusive access
+//! to them and can run in parallel.
+//!
 //! # Supported Bevy Versions
 //!| Bevy | `bevy_simple_preferences` |
 //!| ---- | -----------------------   |
@@ -95,6 +98,71 @@
 //!
 //! Go to the [`crate::storage::fs::FileStorageFormat`] documentation for more information on how to do it.
 //!
+//! ## Sparse storage
+//!
+//! By default, every field of the preferences is stored. Using [`PreferencesPlugin::with_sparse_storage`],
+//! only the fields that differ from the registered defaults are stored, so changing a default in a new
+//! release reaches the users that never modified it.
+//!
+//! ## Profiles
+//!
+//! [`PreferencesProfiles`] manages named profiles, like the players of a shared PC, each one stored in its own
+//! `profiles/{name}` directory next to the default profile. Profiles can be created, copied, renamed and deleted,
+//! and switching saves the current profile and loads the new one into every preferences resource,
+//! sending [`PreferencesProfileSwitched`] and the usual change events. The active profile is restored on launch.
+//!
+//! ## Keyed preferences
+//!
+//! Preferences keyed by a runtime identifier, like per-gamepad settings keyed by device name, are registered using
+//! [`RegisterPreferencesExt::register_keyed_preferences`], and accessed with [`KeyedPreferences`], that creates entries on demand
+//! and detects changes per key.
+//!
+//! ## Existing resources, components and states
+//!
+//! Existing reflected resources, like `ClearColor` or `UiScale`, can be persisted as preferences
+//! using [`RegisterPreferencesExt::persist_resource`], without wrapping them in [`PreferencesResource`].
+//! Components of entities with a stable [`PersistId`], like an editor camera `Transform`, can be persisted
+//! using [`RegisterPreferencesExt::persist_components`].
+//! With the `state` feature, `RegisterPreferencesExt::persist_state` restores the last `States`
+//! on launch, before the first transition.
+//!
+//! ## App metadata
+//!
+//! [`PreferencesPlugin`] stores the first run time, launch count, app version and schema version beside the preferences,
+//! configured with [`PreferencesPlugin::with_app_version`] and [`PreferencesPlugin::with_schema_version`].
+//! They're exposed by the read-only [`AppMetadata`] resource, available by `Startup`, to detect the first run,
+//! upgrades and downgrades.
+//!
+//! ## Hints
+//!
+//! [`PreferencesHintsPlugin`] registers [`HintsPreferences`], to store "don't show again" dialogs and one-time tutorials.
+//! Hints can be dismissed, or snoozed for a wall-clock duration, and reset all at once.
+//!
+//! ## Input bindings
+//!
+//! With the `input` feature, `PreferencesInputPlugin` registers `InputBindings`, that bind named actions to keys,
+//! mouse buttons or gamepad buttons with modifiers. Bindings are stored as `"Ctrl+S"`, conflicts can be detected
+//! and single actions reset, and `ActionInput` tells if an action is pressed.
+//!
+//! ## Settings window
+//!
+//! With the `egui` feature, `PreferencesInspectorPlugin` adds an egui window that lists every registered
+//! preferences type, grouped by [`Category`], and edits them using the metadata described in [`PreferencesSchema`].
+//! `PreferencesEguiMemoryPlugin` persists the egui memory, like window positions, next to the preferences.
+//!
+//! The feature pulls in `bevy_winit`, so on Linux it requires a windowing backend: `bevy/x11` or `bevy/wayland`.
+//! Both are enabled by bevy's default features.
+//!
+//! ## Window geometry
+//!
+//! With the `window` feature, `PreferencesWindowGeometryPlugin` persists the mode, position, size and monitor
+//! of the primary window, and of any window with a `PersistWindowGeometry` component.
+//!
+//! ## Graphics
+//!
+//! With the `graphics` feature, `PreferencesGraphicsPlugin` provides standard `GraphicsPreferences`,
+//! like present mode, MSAA or frame rate limit, with Low, Medium and High presets.
+//!
 use bevy::prelude::*;
 use bevy::reflect::FromType;
 use std::sync::Arc;
@@ -102,14 +170,79 @@ use thiserror::Error;
 
 pub mod serializable_map;
 
+mod changes;
+mod commands;
+mod components;
+mod defaults;
+#[cfg(feature = "egui")]
+mod egui_memory;
+#[cfg(feature = "graphics")]
+mod graphics;
+mod hints;
+mod history;
+#[cfg(feature = "input")]
+mod input;
+#[cfg(feature = "egui")]
+mod inspector;
+mod keyed;
+mod metadata;
 mod plugin;
+mod profiles;
 mod registry;
+mod report;
 mod resource;
+mod schema;
+mod sparse;
+mod staging;
+#[cfg(feature = "state")]
+mod state;
 pub mod storage;
+mod validation;
+#[cfg(feature = "window")]
+mod window_geometry;
 
+pub use crate::changes::{PreferencesChanged, changed_fields, preferences_field_changed};
+pub use crate::commands::PreferencesCommandsExt;
+pub use crate::components::{PersistId, PersistedComponents};
+pub use crate::defaults::{PreferencesDefaults, PreferencesWorldExt};
+#[cfg(feature = "egui")]
+pub use crate::egui_memory::PreferencesEguiMemoryPlugin;
+#[cfg(feature = "graphics")]
+pub use crate::graphics::{GraphicsPreferences, GraphicsPreset, PreferencesGraphicsPlugin};
+pub use crate::hints::{HintState, HintsPreferences, PreferencesHintsPlugin};
+pub use crate::history::{
+    PreferencesHistory, PreferencesHistoryPlugin, RedoPreferences, UndoPreferences,
+};
+#[cfg(feature = "input")]
+pub use crate::input::{
+    ActionInput, InputBindingConflict, InputBindings, InputButton, InputChord, InputModifiers,
+    PreferencesInputPlugin,
+};
+#[cfg(feature = "egui")]
+pub use crate::inspector::{PreferencesInspectorPlugin, PreferencesInspectorWindow};
+pub use crate::keyed::{KeyedPreferences, KeyedPreferencesMap};
+pub use crate::metadata::AppMetadata;
 pub use crate::plugin::PreferencesPlugin;
+pub use crate::profiles::{PreferencesProfileSwitched, PreferencesProfiles};
 pub use crate::registry::RegisterPreferencesExt;
-pub use crate::resource::{Preferences, PreferencesResource};
+pub use crate::report::{PreferencesLoadReport, PreferencesLoadSource};
+pub use crate::resource::{Preferences, PreferencesRef, PreferencesResource};
+pub use crate::schema::{
+    Category, Description, DisplayName, PreferencesFieldSchema, PreferencesMetadata,
+    PreferencesSchema, PreferencesTypeSchema, Step, Unit, WidgetHint,
+};
+pub use crate::staging::{PreferencesReverted, StagedPreferences};
+#[cfg(feature = "state")]
+pub use crate::state::PersistedStates;
+pub use crate::validation::{
+    AllowedVariants, NonEmpty, PreferencesRange, PreferencesValidationFailed,
+    PreferencesValidationIssue, PreferencesValidationStrategy,
+};
+#[cfg(feature = "window")]
+pub use crate::window_geometry::{
+    PersistWindowGeometry, PersistedWindowMode, PreferencesWindowGeometryPlugin, WindowGeometry,
+    WindowGeometryPreferences,
+};
 
 use crate::storage::PreferencesStorage;
 
@@ -137,6 +270,35 @@ pub enum PreferencesError {
     #[error("Type {0} not registered")]
     UnregisteredType(String),
 
+    /// A field path does not exist in the preferences, or it has a different type.
+    #[error("Invalid field path: {0}")]
+    InvalidFieldPath(String),
+
+    /// A profile name is empty, or it contains characters that can't be used in a path.
+    #[error("Invalid profile name: {0:?}")]
+    InvalidProfileName(String),
+
+    /// A profile does not exist.
+    #[error("Profile {0:?} does not exist")]
+    ProfileNotFound(String),
+
+    /// A profile with the same name already exists.
+    #[error("Profile {0:?} already exists")]
+    ProfileAlreadyExists(String),
+
+    /// The active profile can't be renamed or deleted, switch to another profile first.
+    #[error("Profile {0:?} is active")]
+    ProfileIsActive(String),
+
+    /// The storage doesn't support profiles, like custom storages or no storage at all.
+    #[error("The storage does not support profiles")]
+    ProfilesNotSupported,
+
+    /// A string is not a valid [`InputChord`], like `"Ctrl+S"`.
+    #[cfg(feature = "input")]
+    #[error("Invalid input chord: {0}")]
+    InvalidInputChord(String),
+
     #[cfg(target_family = "wasm")]
     /// An error has occurred while storing in either `LocalStorage` or `SessionStorage`.
     #[error("Error getting from storage: {0}")]
@@ -240,9 +402,11 @@ impl PreferencesStorageType {
 pub enum PreferencesSet {
     /// System set used to load preferences, it happens before [`PreStartup`].
     Load,
-    /// System set used to create resources of type [`crate::resource::Preferences`]
+    /// System set used to create resources of type [`crate::resource::Preferences`],
+    /// and to assign the values of the new profile in [`PreUpdate`] after [`crate::PreferencesProfileSwitched`].
     AssignResources,
-    /// Assign values into [`crate::serializable_map::PreferencesSerializableMap`].
+    /// Commits the preferences that actually changed, assigning their values into
+    /// [`crate::serializable_map::PreferencesSerializableMap`] and sending [`PreferencesChanged`] events.
     SetReflectMapValues,
     /// System set used to save preferences, it happens on [`Last`].
     Save,
@@ -259,13 +423,81 @@ pub trait PreferencesType: FromReflect + TypePath {}
 
 impl<T> PreferencesType for T where T: FromReflect + TypePath {}
 
+/// Clones a preferences value using its [`FromReflect`] implementation.
+pub(crate) fn clone_preferences<T: PreferencesType>(value: &T) -> T {
+    T::from_reflect(value).expect("Error while trying to clone value")
+}
+
+/// Returns the seconds since the Unix epoch of a wall-clock time.
+pub(crate) fn unix_seconds(time: bevy::utils::SystemTime) -> u64 {
+    time.duration_since(bevy::utils::SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
 /// Represents the type data registration of a [`PreferencesType`] type.
-/// It doesn't contain any data, so it only serves as a marker type to make sure
-/// a type has been registered as Preferences.
+/// It serves as a marker type to make sure a type has been registered as Preferences,
+/// and allows to access its [`PreferencesResource`] without knowing its type,
+/// or the resource itself for types registered with [`RegisterPreferencesExt::persist_resource`].
 #[derive(Clone)]
-pub struct ReflectPreferences;
+pub struct ReflectPreferences {
+    get: fn(&World) -> Option<&dyn Reflect>,
+    get_mut: fn(&mut World) -> Option<Mut<dyn Reflect>>,
+}
+
+impl ReflectPreferences {
+    /// Returns the current value of the preferences, if its resource exists.
+    pub fn get<'w>(&self, world: &'w World) -> Option<&'w dyn Reflect> {
+        (self.get)(world)
+    }
+
+    /// Returns the current value of the preferences mutably, if its resource exists.
+    pub fn get_mut<'w>(&self, world: &'w mut World) -> Option<Mut<'w, dyn Reflect>> {
+        (self.get_mut)(world)
+    }
+
+    /// Creates the type data of a resource persisted as preferences, that is accessed directly.
+    pub(crate) fn from_resource<R: PreferencesType + Resource>() -> Self {
+        Self {
+            get: |world| {
+                world
+                    .get_resource::<R>()
+                    .map(|resource| resource as &dyn Reflect)
+            },
+            get_mut: |world| {
+                world
+                    .get_resource_mut::<R>()
+                    .map(|resource| resource.map_unchanged(|resource| resource as &mut dyn Reflect))
+            },
+        }
+    }
+}
 
 impl<T: PreferencesType> FromType<T> for ReflectPreferences {
+    fn from_type() -> Self {
+        Self {
+            get: |world| {
+                world
+                    .get_resource::<PreferencesResource<T>>()
+                    .map(|resource| &**resource as &dyn Reflect)
+            },
+            get_mut: |world| {
+                world
+                    .get_resource_mut::<PreferencesResource<T>>()
+                    .map(|resource| {
+                        resource.map_unchanged(|resource| &mut **resource as &mut dyn Reflect)
+                    })
+            },
+        }
+    }
+}
+
+/// Type data of preferences managed by this crate, that are not shown in [`PreferencesSchema`].
+/// They're kept when switching profiles.
+#[derive(Clone)]
+pub(crate) struct ReflectInternalPreferences;
+
+impl<T> FromType<T> for ReflectInternalPreferences {
     fn from_type() -> Self {
         Self
     }
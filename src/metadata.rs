@@ -0,0 +1,305 @@
+use crate::plugin::LoadPreferences;
+use crate::serializable_map::PreferencesSerializableMap;
+use crate::{PreferencesSet, ReflectInternalPreferences, ReflectPreferences, unix_seconds};
+use bevy::prelude::*;
+use bevy::reflect::ReflectFromReflect;
+use bevy::utils::SystemTime;
+use std::cmp::Ordering;
+use std::time::Duration;
+
+/// Metadata stored beside the user preferences, updated on every launch.
+/// Its type path is fixed, since it's the key it's stored under.
+#[derive(Reflect, Clone, Debug, Default, PartialEq)]
+#[type_path = "bevy_simple_preferences"]
+struct StoredAppMetadata {
+    first_run_time: u64,
+    launch_count: u64,
+    app_version: String,
+    schema_version: u32,
+}
+
+/// Metadata of the application launches, managed by [`crate::PreferencesPlugin`].
+/// It's inserted when the preferences are loaded, so it's available by [`Startup`].
+///
+/// The current versions are configured with [`crate::PreferencesPlugin::with_app_version`]
+/// and [`crate::PreferencesPlugin::with_schema_version`].
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_simple_preferences::*;
+/// App::new()
+///     .add_plugins(MinimalPlugins)
+///     .add_plugins(
+///         PreferencesPlugin::with_no_persistence()
+///             .with_app_version(env!("CARGO_PKG_VERSION"))
+///     )
+///     .add_systems(Startup, |metadata: Res<AppMetadata>| {
+///         if metadata.is_first_run() {
+///             info!("Welcome!");
+///         } else if let Some(previous_version) = metadata.upgraded_from() {
+///             info!("What's new since {previous_version}");
+///         }
+///     });
+/// ```
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct AppMetadata {
+    current: StoredAppMetadata,
+    previous: Option<StoredAppMetadata>,
+}
+
+impl AppMetadata {
+    /// Returns true if nothing was stored by a previous launch.
+    pub fn is_first_run(&self) -> bool {
+        self.previous.is_none()
+    }
+
+    /// Wall-clock time of the first launch.
+    pub fn first_run_time(&self) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(self.current.first_run_time)
+    }
+
+    /// Number of launches, including the current one.
+    pub fn launch_count(&self) -> u64 {
+        self.current.launch_count
+    }
+
+    /// Version of the current launch, empty if it's not configured.
+    pub fn app_version(&self) -> &str {
+        &self.current.app_version
+    }
+
+    /// Version of the previous launch, empty if it was not configured.
+    pub fn previous_app_version(&self) -> Option<&str> {
+        self.previous
+            .as_ref()
+            .map(|previous| previous.app_version.as_str())
+    }
+
+    /// Preferences schema version of the current launch.
+    pub fn schema_version(&self) -> u32 {
+        self.current.schema_version
+    }
+
+    /// Preferences schema version of the previous launch.
+    pub fn previous_schema_version(&self) -> Option<u32> {
+        self.previous
+            .as_ref()
+            .map(|previous| previous.schema_version)
+    }
+
+    /// Returns the version of the previous launch, if it's older than the current one.
+    ///
+    /// Versions are compared by their numeric components, ignoring any pre-release or build suffix.
+    pub fn upgraded_from(&self) -> Option<&str> {
+        self.previous_app_version()
+            .filter(|previous| compare_versions(previous, self.app_version()) == Ordering::Less)
+    }
+
+    /// Returns the version of the previous launch, if it's newer than the current one,
+    /// so the stored preferences may have been written by a newer release.
+    pub fn downgraded_from(&self) -> Option<&str> {
+        self.previous_app_version()
+            .filter(|previous| compare_versions(previous, self.app_version()) == Ordering::Greater)
+    }
+
+    /// Returns the schema version of the previous launch, if it's older than the current one.
+    pub fn schema_upgraded_from(&self) -> Option<u32> {
+        self.previous_schema_version()
+            .filter(|previous| *previous < self.schema_version())
+    }
+
+    /// Returns the schema version of the previous launch, if it's newer than the current one.
+    pub fn schema_downgraded_from(&self) -> Option<u32> {
+        self.previous_schema_version()
+            .filter(|previous| *previous > self.schema_version())
+    }
+}
+
+// Unknown versions are never older nor newer
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    fn numbers(version: &str) -> Vec<u64> {
+        let release = version.split(['-', '+']).next().unwrap_or_default();
+        let mut numbers: Vec<u64> = release
+            .split('.')
+            .map(|number| number.trim().parse().unwrap_or(0))
+            .collect();
+        while numbers.last() == Some(&0) {
+            numbers.pop();
+        }
+        numbers
+    }
+
+    if a.is_empty() || b.is_empty() {
+        return Ordering::Equal;
+    }
+    numbers(a).cmp(&numbers(b))
+}
+
+pub(crate) struct AppMetadataPlugin {
+    pub app_version: Option<&'static str>,
+    pub schema_version: u32,
+}
+
+impl Plugin for AppMetadataPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<StoredAppMetadata>()
+            .register_type_data::<StoredAppMetadata, ReflectPreferences>()
+            .register_type_data::<StoredAppMetadata, ReflectFromReflect>()
+            .register_type_data::<StoredAppMetadata, ReflectInternalPreferences>()
+            .add_systems(
+                LoadPreferences,
+                update_app_metadata(self.app_version.unwrap_or_default(), self.schema_version)
+                    .after(PreferencesSet::Load),
+            );
+    }
+}
+
+fn update_app_metadata(
+    app_version: &'static str,
+    schema_version: u32,
+) -> impl Fn(Commands, Option<ResMut<PreferencesSerializableMap>>) {
+    move |mut commands, mut storage_map| {
+        let previous = storage_map.as_mut().and_then(|storage_map| {
+            storage_map
+                .bypass_change_detection()
+                .resolve_sparse(&StoredAppMetadata::default());
+            storage_map.get::<StoredAppMetadata>().cloned()
+        });
+
+        let current = StoredAppMetadata {
+            first_run_time: previous
+                .as_ref()
+                .map(|previous| previous.first_run_time)
+                .unwrap_or_else(|| unix_seconds(SystemTime::now())),
+            launch_count: previous
+                .as_ref()
+                .map_or(0, |previous| previous.launch_count)
+                + 1,
+            app_version: app_version.to_string(),
+            schema_version,
+        };
+
+        if let Some(storage_map) = &mut storage_map {
+            storage_map.set(current.clone());
+        }
+
+        let metadata = AppMetadata { current, previous };
+        debug!("Launching with {metadata:?}");
+        commands.insert_resource(metadata);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::compare_versions;
+    use crate::{
+        AppMetadata, Preferences, PreferencesPlugin, PreferencesSchema, PreferencesStorageType,
+        RegisterPreferencesExt,
+    };
+    use bevy::core::{FrameCount, FrameCountPlugin};
+    use bevy::prelude::*;
+    use bevy::time::TimePlugin;
+    use std::cmp::Ordering;
+    use std::path::Path;
+
+    fn run_app(path: &Path, app_version: &'static str, schema_version: u32) -> AppMetadata {
+        let mut app = App::new();
+        app.add_plugins((TimePlugin, FrameCountPlugin))
+            .add_plugins(
+                PreferencesPlugin::persisted_with_app_name("PreferencesTest")
+                    .with_storage_type(PreferencesStorageType::FileSystemWithParentDirectory(
+                        path.into(),
+                    ))
+                    .with_app_version(app_version)
+                    .with_schema_version(schema_version),
+            )
+            .add_systems(
+                PostUpdate,
+                |mut app_exit: EventWriter<AppExit>, frame_count: Res<FrameCount>| {
+                    if frame_count.0 > 0 {
+                        app_exit.send_default();
+                    }
+                },
+            );
+        app.update();
+        app.update();
+        app.world().resource::<AppMetadata>().clone()
+    }
+
+    #[test]
+    fn test_launches_are_tracked_across_runs() {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        let metadata = run_app(temp_dir.path(), "1.0.0", 1);
+        assert!(metadata.is_first_run());
+        assert_eq!(metadata.launch_count(), 1);
+        assert_eq!(metadata.upgraded_from(), None);
+
+        let metadata = run_app(temp_dir.path(), "1.1.0", 2);
+        assert!(!metadata.is_first_run());
+        assert_eq!(metadata.launch_count(), 2);
+        assert_eq!(metadata.upgraded_from(), Some("1.0.0"));
+        assert_eq!(metadata.schema_upgraded_from(), Some(1));
+        assert_eq!(metadata.downgraded_from(), None);
+
+        let first_run_time = metadata.first_run_time();
+        let metadata = run_app(temp_dir.path(), "1.0.0", 1);
+        assert_eq!(metadata.launch_count(), 3);
+        assert_eq!(metadata.first_run_time(), first_run_time);
+        assert_eq!(metadata.upgraded_from(), None);
+        assert_eq!(metadata.downgraded_from(), Some("1.1.0"));
+        assert_eq!(metadata.schema_downgraded_from(), Some(2));
+    }
+
+    #[test]
+    fn test_metadata_does_not_collide_with_user_types() {
+        #[derive(Reflect, Default)]
+        struct StoredAppMetadata {
+            launch_count: u64,
+        }
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        for launch_count in 1..=3 {
+            let mut app = App::new();
+            app.add_plugins(TimePlugin).add_plugins(
+                PreferencesPlugin::persisted_with_app_name("PreferencesTest").with_storage_type(
+                    PreferencesStorageType::FileSystemWithParentDirectory(temp_dir.path().into()),
+                ),
+            );
+            // The user type is added by a later version of the app
+            if launch_count > 1 {
+                app.register_preferences::<StoredAppMetadata>().add_systems(
+                    Update,
+                    |mut stored: Preferences<StoredAppMetadata>| {
+                        stored.launch_count = 10;
+                    },
+                );
+            }
+            app.update();
+            app.world_mut().send_event(AppExit::Success);
+            app.update();
+
+            let metadata = app.world().resource::<AppMetadata>();
+            assert_eq!(metadata.launch_count(), launch_count);
+        }
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("1.9.0", "1.10.0"), Ordering::Less);
+        assert_eq!(compare_versions("1.0", "1.0.0"), Ordering::Equal);
+        assert_eq!(compare_versions("2.0.0-beta", "1.9"), Ordering::Greater);
+        assert_eq!(compare_versions("", "1.0.0"), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_metadata_is_not_in_the_schema() {
+        let mut app = App::new();
+        app.add_plugins(PreferencesPlugin::with_no_persistence());
+        let registry = app.world().resource::<AppTypeRegistry>().read();
+        assert!(
+            PreferencesSchema::new(&registry)
+                .get("StoredAppMetadata")
+                .is_none()
+        );
+    }
+}
@@ -1,3 +1,6 @@
+use crate::metadata::AppMetadataPlugin;
+use crate::profiles::{PROFILES_KEY, PreferencesProfiles, PreferencesProfilesPlugin};
+use crate::report::PreferencesLoadReport;
 use crate::serializable_map::PreferencesSerializableMap;
 use crate::storage::{PreferencesStorage, PreferencesStorageResource};
 use std::sync::Arc;
@@ -14,12 +17,16 @@ use std::time::Duration;
 
 /// Struct responsible for deciding where to store the preferences.
 #[derive(Clone)]
-struct PreferencesStorageBuilder {
+pub(crate) struct PreferencesStorageBuilder {
     pub app_name: Option<&'static str>,
     pub org_name: Option<&'static str>,
     pub storage_type: PreferencesStorageType,
+    pub sparse_storage: bool,
 }
 
+/// Directory, or key prefix, where the storage of every named profile is created.
+const PROFILES_DIRECTORY: &str = "profiles";
+
 impl PreferencesStorageBuilder {
     fn full_app_name(&self) -> Option<String> {
         match (self.app_name, self.org_name) {
@@ -33,24 +40,38 @@ impl PreferencesStorageBuilder {
     #[cfg(not(target_family = "wasm"))]
     fn get_storage_parent_path_and_format(
         &self,
+        profile: Option<&str>,
     ) -> Option<(std::path::PathBuf, crate::storage::fs::FileStorageFormatFns)> {
         let file_storage_path = self.storage_type.file_storage_path()?;
         let file_storage_format = self.storage_type.file_storage_format()?;
         let app_name = self.full_app_name()?;
-        Some((file_storage_path.join(app_name), file_storage_format))
+        let parent_path = file_storage_path.join(app_name);
+        let parent_path = match profile {
+            Some(profile) => parent_path.join(PROFILES_DIRECTORY).join(profile),
+            None => parent_path,
+        };
+        Some((parent_path, file_storage_format))
     }
 
-    fn create_storage(&self) -> Option<PreferencesStorageResource> {
+    /// Creates the storage of a named profile, or the default one if `profile` is `None`.
+    /// Custom storages only have the default profile.
+    pub(crate) fn create_profile_storage(
+        &self,
+        profile: Option<&str>,
+    ) -> Option<PreferencesStorageResource> {
         if let PreferencesStorageType::Custom(custom) = &self.storage_type {
-            return Some(PreferencesStorageResource::from_arc(custom.clone()));
+            return match profile {
+                Some(_) => None,
+                None => Some(PreferencesStorageResource::from_arc(custom.clone())),
+            };
         }
-        self.create_native_storage()
+        self.create_native_storage(profile)
     }
 
     #[cfg(not(target_family = "wasm"))]
-    fn create_native_storage(&self) -> Option<PreferencesStorageResource> {
+    fn create_native_storage(&self, profile: Option<&str>) -> Option<PreferencesStorageResource> {
         let storage =
-            self.get_storage_parent_path_and_format()
+            self.get_storage_parent_path_and_format(profile)
                 .and_then(|(parent_path, format)| {
                     crate::storage::fs::FileStorage::new_with_format(parent_path, format).ok()
                 });
@@ -59,13 +80,100 @@ impl PreferencesStorageBuilder {
     }
 
     #[cfg(target_family = "wasm")]
-    fn create_native_storage(&self) -> Option<PreferencesStorageResource> {
+    fn gloo_storage(&self, profile: Option<&str>) -> Option<crate::storage::gloo::GlooStorage> {
         let app_name = self.full_app_name()?;
-        let storage = self
-            .storage_type
-            .gloo_storage(format!("{app_name}_preferences"))?;
+        let preferences_key = match profile {
+            Some(profile) => format!("{app_name}_{PROFILES_DIRECTORY}/{profile}/preferences"),
+            None => format!("{app_name}_preferences"),
+        };
+        self.storage_type.gloo_storage(preferences_key)
+    }
+
+    #[cfg(target_family = "wasm")]
+    fn create_native_storage(&self, profile: Option<&str>) -> Option<PreferencesStorageResource> {
+        let storage = self.gloo_storage(profile)?;
         Some(PreferencesStorageResource::new(storage))
     }
+
+    /// Copies everything stored by the profile `from` into the profile `to`, except the profiles list.
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) fn copy_profile(&self, from: Option<&str>, to: &str) -> crate::Result<()> {
+        let (Some((from_path, _)), Some((to_path, _))) = (
+            self.get_storage_parent_path_and_format(from),
+            self.get_storage_parent_path_and_format(Some(to)),
+        ) else {
+            return Err(crate::PreferencesError::ProfilesNotSupported);
+        };
+
+        std::fs::create_dir_all(&to_path)?;
+        let entries = match std::fs::read_dir(&from_path) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(err.into()),
+        };
+
+        for entry in entries {
+            let entry = entry?;
+            if entry.file_type()?.is_file() && entry.file_name() != PROFILES_KEY {
+                std::fs::copy(entry.path(), to_path.join(entry.file_name()))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Moves everything stored by the profile `from` into the profile `to`.
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) fn rename_profile(&self, from: &str, to: &str) -> crate::Result<()> {
+        let (Some((from_path, _)), Some((to_path, _))) = (
+            self.get_storage_parent_path_and_format(Some(from)),
+            self.get_storage_parent_path_and_format(Some(to)),
+        ) else {
+            return Err(crate::PreferencesError::ProfilesNotSupported);
+        };
+
+        match std::fs::rename(from_path, to_path) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Removes everything stored by the profile.
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) fn delete_profile(&self, profile: &str) -> crate::Result<()> {
+        let Some((path, _)) = self.get_storage_parent_path_and_format(Some(profile)) else {
+            return Err(crate::PreferencesError::ProfilesNotSupported);
+        };
+
+        match std::fs::remove_dir_all(path) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Copies everything stored by the profile `from` into the profile `to`, except the profiles list.
+    #[cfg(target_family = "wasm")]
+    pub(crate) fn copy_profile(&self, from: Option<&str>, to: &str) -> crate::Result<()> {
+        let (Some(from), Some(to)) = (self.gloo_storage(from), self.gloo_storage(Some(to))) else {
+            return Err(crate::PreferencesError::ProfilesNotSupported);
+        };
+        from.copy_to(&to, PROFILES_KEY)
+    }
+
+    /// Moves everything stored by the profile `from` into the profile `to`.
+    #[cfg(target_family = "wasm")]
+    pub(crate) fn rename_profile(&self, from: &str, to: &str) -> crate::Result<()> {
+        self.copy_profile(Some(from), to)?;
+        self.delete_profile(from)
+    }
+
+    /// Removes everything stored by the profile.
+    #[cfg(target_family = "wasm")]
+    pub(crate) fn delete_profile(&self, profile: &str) -> crate::Result<()> {
+        let Some(storage) = self.gloo_storage(Some(profile)) else {
+            return Err(crate::PreferencesError::ProfilesNotSupported);
+        };
+        storage.remove()
+    }
 }
 
 /// Schedule label that is executed before `PreStartup`
@@ -101,6 +209,13 @@ pub struct PreferencesPlugin {
     pub org_name: Option<&'static str>,
     /// Type of storage, [`PreferencesStorageType::DefaultStorage`] by default.
     pub storage_type: PreferencesStorageType,
+    /// If true, only values that differ from the defaults are stored. False by default.
+    /// See [`Self::with_sparse_storage`].
+    pub sparse_storage: bool,
+    /// Version of the application, stored in [`crate::AppMetadata`]. See [`Self::with_app_version`].
+    pub app_version: Option<&'static str>,
+    /// Version of the preferences schema, stored in [`crate::AppMetadata`]. 0 by default.
+    pub schema_version: u32,
 }
 
 impl PreferencesPlugin {
@@ -116,6 +231,9 @@ impl PreferencesPlugin {
             app_name: Some(app_name),
             org_name: None,
             storage_type: Default::default(),
+            sparse_storage: false,
+            app_version: None,
+            schema_version: 0,
         }
     }
 
@@ -126,6 +244,9 @@ impl PreferencesPlugin {
             app_name: None,
             org_name: None,
             storage_type: PreferencesStorageType::NoStorage,
+            sparse_storage: false,
+            app_version: None,
+            schema_version: 0,
         }
     }
 
@@ -135,6 +256,44 @@ impl PreferencesPlugin {
         self
     }
 
+    /// Only stores the values that differ from the registered defaults, so changing a default
+    /// in a new release reaches users that never modified it.
+    ///
+    /// Nested structs are stored field by field, any other value is stored as a whole.
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_simple_preferences::PreferencesPlugin;
+    /// App::new()
+    ///         .add_plugins(MinimalPlugins)
+    ///         .add_plugins(PreferencesPlugin::persisted_with_app_name("MyPreferencesAppName").with_sparse_storage())
+    /// # ;
+    /// ```
+    pub fn with_sparse_storage(mut self) -> Self {
+        self.sparse_storage = true;
+        self
+    }
+
+    /// Specifies the version of the application, to detect upgrades and downgrades with [`crate::AppMetadata`].
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_simple_preferences::PreferencesPlugin;
+    /// App::new()
+    ///         .add_plugins(MinimalPlugins)
+    ///         .add_plugins(PreferencesPlugin::persisted_with_app_name("MyPreferencesAppName").with_app_version(env!("CARGO_PKG_VERSION")))
+    /// # ;
+    /// ```
+    pub fn with_app_version(mut self, app_version: &'static str) -> Self {
+        self.app_version = Some(app_version);
+        self
+    }
+
+    /// Specifies the version of the preferences schema, to detect when the stored preferences
+    /// were written by an older or newer schema with [`crate::AppMetadata`].
+    pub fn with_schema_version(mut self, schema_version: u32) -> Self {
+        self.schema_version = schema_version;
+        self
+    }
+
     /// Specifies a fully custom Preferences Storage
     /// ```
     /// # use bevy::prelude::*;
@@ -165,6 +324,9 @@ impl PreferencesPlugin {
             app_name: None,
             org_name: None,
             storage_type: PreferencesStorageType::Custom(Arc::new(storage)),
+            sparse_storage: false,
+            app_version: None,
+            schema_version: 0,
         }
     }
 
@@ -173,6 +335,7 @@ impl PreferencesPlugin {
             app_name: self.app_name,
             org_name: self.org_name,
             storage_type: self.storage_type.clone(),
+            sparse_storage: self.sparse_storage,
         }
     }
 }
@@ -192,61 +355,102 @@ impl Plugin for PreferencesPlugin {
             world.add_schedule(schedule);
         }
 
-        app.add_event::<PreferencesSaved>()
-            .add_systems(
-                LoadPreferences,
-                load_preferences(self.storage_builder()).in_set(PreferencesSet::Load),
-            )
-            .configure_sets(
-                Last,
-                PreferencesSet::SetReflectMapValues.before(PreferencesSet::Save),
-            )
-            // We need to hook on Last to catch AppExit event correctly
-            .add_systems(
-                Last,
-                save_preferences.in_set(PreferencesSet::Save).run_if(
-                    resource_exists::<PreferencesStorageResource>
-                        .and(resource_exists::<PreferencesSerializableMap>),
-                ),
-            );
+        app.add_plugins((
+            AppMetadataPlugin {
+                app_version: self.app_version,
+                schema_version: self.schema_version,
+            },
+            PreferencesProfilesPlugin,
+        ))
+        .add_event::<PreferencesSaved>()
+        .init_resource::<PreferencesLoadReport>()
+        .add_systems(
+            LoadPreferences,
+            load_preferences(self.storage_builder()).in_set(PreferencesSet::Load),
+        )
+        .configure_sets(
+            Last,
+            PreferencesSet::SetReflectMapValues.before(PreferencesSet::Save),
+        )
+        // We need to hook on Last to catch AppExit event correctly
+        .add_systems(
+            Last,
+            save_preferences.in_set(PreferencesSet::Save).run_if(
+                resource_exists::<PreferencesStorageResource>
+                    .and(resource_exists::<PreferencesSerializableMap>),
+            ),
+        );
     }
 }
 
 fn load_preferences(
     storage_builder: PreferencesStorageBuilder,
-) -> impl Fn(Commands, Res<AppTypeRegistry>) {
-    move |mut commands: Commands, app_type_registry: Res<AppTypeRegistry>| {
-        let type_registry_arc = TypeRegistryArc::clone(&app_type_registry);
-        let Some(storage) = storage_builder.create_storage() else {
+) -> impl Fn(Commands, Res<AppTypeRegistry>, ResMut<PreferencesLoadReport>) {
+    move |mut commands: Commands,
+          app_type_registry: Res<AppTypeRegistry>,
+          mut report: ResMut<PreferencesLoadReport>| {
+        let profiles = PreferencesProfiles::load(storage_builder.clone());
+        let storage = storage_builder.create_profile_storage(profiles.active());
+        commands.insert_resource(profiles);
+
+        let Some(storage) = storage else {
             return;
         };
 
-        let seed = PreferencesSerializableMap::deserialize_seed(type_registry_arc.clone());
-
-        let preferences = match storage.load_preferences(seed) {
-            Ok(preferences) => preferences,
-            #[cfg(not(target_family = "wasm"))]
-            Err(crate::PreferencesError::IoError(io_error)) => {
-                if io_error.kind() != std::io::ErrorKind::NotFound {
-                    error!("I/O Error loading preferences: {io_error}");
-                }
-                PreferencesSerializableMap::empty(type_registry_arc)
-            }
-            #[cfg(target_family = "wasm")]
-            Err(crate::PreferencesError::GlooError(
-                gloo_storage::errors::StorageError::KeyNotFound(_),
-            )) => PreferencesSerializableMap::empty(type_registry_arc),
-            Err(err) => {
-                error!("Unknown Error loading preferences: {err:?}");
-                PreferencesSerializableMap::empty(type_registry_arc)
-            }
-        };
+        let preferences = load_storage_map(
+            &*storage,
+            storage_builder.sparse_storage,
+            TypeRegistryArc::clone(&app_type_registry),
+            &mut report,
+        );
 
         commands.insert_resource(preferences);
         commands.insert_resource(storage);
     }
 }
 
+/// Loads the preferences from `storage`, falling back to an empty map if they can't be loaded.
+pub(crate) fn load_storage_map(
+    storage: &dyn PreferencesStorage,
+    sparse_storage: bool,
+    type_registry_arc: TypeRegistryArc,
+    report: &mut PreferencesLoadReport,
+) -> PreferencesSerializableMap {
+    let (seed, empty_map): (_, fn(TypeRegistryArc) -> PreferencesSerializableMap) =
+        if sparse_storage {
+            (
+                PreferencesSerializableMap::deserialize_sparse_seed(type_registry_arc.clone()),
+                PreferencesSerializableMap::empty_sparse,
+            )
+        } else {
+            (
+                PreferencesSerializableMap::deserialize_seed(type_registry_arc.clone()),
+                PreferencesSerializableMap::empty,
+            )
+        };
+
+    match storage.load_preferences(seed) {
+        Ok(preferences) => preferences,
+        #[cfg(not(target_family = "wasm"))]
+        Err(crate::PreferencesError::IoError(io_error)) => {
+            if io_error.kind() != std::io::ErrorKind::NotFound {
+                error!("I/O Error loading preferences: {io_error}");
+                report.set_load_error(io_error);
+            }
+            empty_map(type_registry_arc)
+        }
+        #[cfg(target_family = "wasm")]
+        Err(crate::PreferencesError::GlooError(
+            gloo_storage::errors::StorageError::KeyNotFound(_),
+        )) => empty_map(type_registry_arc),
+        Err(err) => {
+            error!("Unknown Error loading preferences: {err:?}");
+            report.set_load_error(err);
+            empty_map(type_registry_arc)
+        }
+    }
+}
+
 /// Event triggered every time the preferences are saved to the background
 #[derive(Event, Copy, Clone, PartialEq, Eq, Hash, Default)]
 pub struct PreferencesSaved;
@@ -0,0 +1,489 @@
+use crate::plugin::{PreferencesStorageBuilder, load_storage_map};
+use crate::report::PreferencesLoadReport;
+use crate::serializable_map::PreferencesSerializableMap;
+use crate::storage::PreferencesStorageResource;
+use crate::{PreferencesError, PreferencesSet, ReflectInternalPreferences, Result};
+use bevy::prelude::*;
+
+/// Side data of the default profile with the list of profiles.
+/// The first line is the active profile, empty for the default one, followed by a line per named profile.
+pub(crate) const PROFILES_KEY: &str = "profiles.txt";
+
+/// Event sent when the active profile is switched with [`PreferencesProfiles::switch`].
+///
+/// It's sent once the preferences of the new profile are loaded, and they're assigned
+/// to every preferences resource in the next [`PreUpdate`], sending [`crate::PreferencesChanged`]
+/// for the preferences that differ.
+#[derive(Event, Clone, Debug, PartialEq, Eq)]
+pub struct PreferencesProfileSwitched {
+    /// Profile that was active before, `None` for the default profile.
+    pub previous: Option<String>,
+    /// Profile that is active now, `None` for the default profile.
+    pub active: Option<String>,
+}
+
+/// Named profiles, each one with its own preferences, like the players of a shared PC.
+/// It's inserted by [`crate::PreferencesPlugin`] when the preferences are loaded.
+///
+/// The default profile is stored where the preferences are stored without profiles,
+/// and every named profile in its own `profiles/{name}` directory next to it.
+/// Custom storages only have the default profile.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_simple_preferences::*;
+/// fn switch_to_player_two(mut profiles: ResMut<PreferencesProfiles>) {
+///     if !profiles.contains("Player 2") {
+///         profiles.create("Player 2").unwrap();
+///     }
+///     profiles.switch(Some("Player 2")).unwrap();
+/// }
+/// ```
+#[derive(Resource)]
+pub struct PreferencesProfiles {
+    storage_builder: PreferencesStorageBuilder,
+    names: Vec<String>,
+    active: Option<String>,
+    pending_switch: Option<Option<String>>,
+    // Profiles copied from the active one, whose preferences may not be saved yet
+    pending_copies: Vec<String>,
+}
+
+impl PreferencesProfiles {
+    pub(crate) fn load(storage_builder: PreferencesStorageBuilder) -> Self {
+        let mut profiles = Self {
+            storage_builder,
+            names: Vec::new(),
+            active: None,
+            pending_switch: None,
+            pending_copies: Vec::new(),
+        };
+
+        let Some(storage) = profiles.storage_builder.create_profile_storage(None) else {
+            return profiles;
+        };
+        let data = match storage.load_side_data(PROFILES_KEY) {
+            Ok(Some(data)) => data,
+            Ok(None) => return profiles,
+            Err(err) => {
+                error!("Error loading profiles: {err}");
+                return profiles;
+            }
+        };
+
+        let mut lines = data.lines();
+        let active = lines.next().unwrap_or_default();
+        profiles.names = lines
+            .filter(|name| is_valid_profile_name(name))
+            .map(str::to_string)
+            .collect();
+        if profiles.contains(active) {
+            profiles.active = Some(active.to_string());
+        }
+        profiles
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(storage) = self.storage_builder.create_profile_storage(None) else {
+            return Err(PreferencesError::ProfilesNotSupported);
+        };
+
+        let mut data = self.active.clone().unwrap_or_default();
+        for name in &self.names {
+            data.push('\n');
+            data.push_str(name);
+        }
+        storage.save_side_data(PROFILES_KEY, &data)
+    }
+
+    /// Active profile, `None` for the default profile.
+    pub fn active(&self) -> Option<&str> {
+        self.active.as_deref()
+    }
+
+    /// Iterator over the names of every profile, except the default one.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(String::as_str)
+    }
+
+    /// Returns true if a profile named `name` exists.
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.iter().any(|existing| existing == name)
+    }
+
+    // Profiles that are active, or about to be, can't be renamed or deleted
+    fn check_is_not_active(&self, name: &str) -> Result<()> {
+        let pending = self.pending_switch.as_ref().and_then(Option::as_deref);
+        if self.active() == Some(name) || pending == Some(name) {
+            return Err(PreferencesError::ProfileIsActive(name.to_string()));
+        }
+        Ok(())
+    }
+
+    fn check_exists(&self, name: &str) -> Result<()> {
+        if !self.contains(name) {
+            return Err(PreferencesError::ProfileNotFound(name.to_string()));
+        }
+        Ok(())
+    }
+
+    fn check_new_name(&self, name: &str) -> Result<()> {
+        if !is_valid_profile_name(name) {
+            return Err(PreferencesError::InvalidProfileName(name.to_string()));
+        }
+        if self.contains(name) {
+            return Err(PreferencesError::ProfileAlreadyExists(name.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Creates a profile with the default preferences.
+    pub fn create(&mut self, name: impl Into<String>) -> Result<()> {
+        let name = name.into();
+        self.check_new_name(&name)?;
+        if self
+            .storage_builder
+            .create_profile_storage(Some(&name))
+            .is_none()
+        {
+            return Err(PreferencesError::ProfilesNotSupported);
+        }
+
+        self.names.push(name);
+        self.save()
+    }
+
+    /// Creates a profile with the stored preferences of the profile `from`, `None` for the default profile.
+    ///
+    /// When `from` is the active profile, the preferences of the new profile are overwritten
+    /// at the end of the frame with the current ones, including the changes that are not saved yet.
+    pub fn copy(&mut self, from: Option<&str>, to: impl Into<String>) -> Result<()> {
+        let to = to.into();
+        if let Some(from) = from {
+            self.check_exists(from)?;
+        }
+        self.check_new_name(&to)?;
+
+        self.storage_builder.copy_profile(from, &to)?;
+        if from == self.active() {
+            self.pending_copies.push(to.clone());
+        }
+        self.names.push(to);
+        self.save()
+    }
+
+    /// Renames a profile that is not active.
+    pub fn rename(&mut self, from: &str, to: impl Into<String>) -> Result<()> {
+        let to = to.into();
+        self.check_exists(from)?;
+        self.check_is_not_active(from)?;
+        self.check_new_name(&to)?;
+
+        self.storage_builder.rename_profile(from, &to)?;
+        for name in self.names.iter_mut().chain(&mut self.pending_copies) {
+            if name == from {
+                name.clone_from(&to);
+            }
+        }
+        self.save()
+    }
+
+    /// Deletes a profile that is not active, with all its preferences.
+    pub fn delete(&mut self, name: &str) -> Result<()> {
+        self.check_exists(name)?;
+        self.check_is_not_active(name)?;
+
+        self.storage_builder.delete_profile(name)?;
+        self.names.retain(|existing| existing != name);
+        self.pending_copies.retain(|existing| existing != name);
+        self.save()
+    }
+
+    /// Switches to the profile `name`, `None` for the default profile, at the end of the frame.
+    /// The preferences of the current profile are saved, and the ones of the new profile are loaded.
+    /// See [`PreferencesProfileSwitched`].
+    pub fn switch(&mut self, name: Option<&str>) -> Result<()> {
+        if let Some(name) = name {
+            self.check_exists(name)?;
+        }
+
+        let name = name.map(str::to_string);
+        self.pending_switch = (name != self.active).then_some(name);
+        Ok(())
+    }
+}
+
+/// Profile names are used as directory names, so they can't contain path separators or reserved characters,
+/// nor be reserved names on any platform.
+fn is_valid_profile_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.trim() == name
+        && !name.starts_with('.')
+        && !name.ends_with('.')
+        && !name
+            .chars()
+            .any(|c| c.is_control() || r#"/\:*?"<>|"#.contains(c))
+        && !is_reserved_windows_name(name)
+}
+
+// Device names are reserved on Windows, even with an extension, like `NUL.txt`
+fn is_reserved_windows_name(name: &str) -> bool {
+    let stem = name.split('.').next().unwrap_or_default().trim_end();
+    let stem = stem.to_ascii_uppercase();
+    matches!(
+        stem.as_bytes(),
+        b"CON"
+            | b"PRN"
+            | b"AUX"
+            | b"NUL"
+            | [b'C', b'O', b'M', b'1'..=b'9']
+            | [b'L', b'P', b'T', b'1'..=b'9']
+    )
+}
+
+pub(crate) struct PreferencesProfilesPlugin;
+
+impl Plugin for PreferencesProfilesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PreferencesProfileSwitched>().add_systems(
+            Last,
+            (save_copied_profiles, switch_profile)
+                .chain()
+                .after(PreferencesSet::SetReflectMapValues)
+                .before(PreferencesSet::Save)
+                .run_if(resource_exists::<PreferencesProfiles>),
+        );
+    }
+}
+
+// The stored preferences of the active profile can be outdated until they're saved,
+// so the profiles copied from it get the ones in memory
+fn save_copied_profiles(
+    mut profiles: ResMut<PreferencesProfiles>,
+    map: Option<Res<PreferencesSerializableMap>>,
+) {
+    if profiles.pending_copies.is_empty() {
+        return;
+    }
+    let pending_copies = std::mem::take(&mut profiles.pending_copies);
+    let Some(map) = map else {
+        return;
+    };
+
+    for name in pending_copies {
+        let Some(storage) = profiles.storage_builder.create_profile_storage(Some(&name)) else {
+            continue;
+        };
+        if let Err(err) = storage.save_preferences(&map) {
+            error!("Error saving preferences of profile {name:?}: {err}");
+        }
+    }
+}
+
+// Runs once the changes of the current profile are committed, so none of them is lost
+fn switch_profile(world: &mut World) {
+    let mut profiles = world.resource_mut::<PreferencesProfiles>();
+    let Some(active) = profiles.pending_switch.take() else {
+        return;
+    };
+    let storage_builder = profiles.storage_builder.clone();
+
+    let Some(storage) = storage_builder.create_profile_storage(active.as_deref()) else {
+        error!("Error creating the storage of profile {active:?}");
+        return;
+    };
+
+    let previous_map = world.remove_resource::<PreferencesSerializableMap>();
+    if let (Some(previous_storage), Some(previous_map)) = (
+        world.get_resource::<PreferencesStorageResource>(),
+        &previous_map,
+    ) {
+        if let Err(err) = previous_storage.save_preferences(previous_map) {
+            error!("Error saving preferences: {err}");
+        }
+    }
+
+    let type_registry_arc = world.resource::<AppTypeRegistry>().0.clone();
+    let mut report = PreferencesLoadReport::default();
+    let mut map = load_storage_map(
+        &*storage,
+        storage_builder.sparse_storage,
+        type_registry_arc.clone(),
+        &mut report,
+    );
+
+    // Preferences managed by this crate, like the app metadata, are kept across profiles
+    if let Some(previous_map) = &previous_map {
+        let type_registry = type_registry_arc.read();
+        for value in previous_map.iter_values() {
+            let type_id = value.reflect_type_info().type_id();
+            if type_registry
+                .get_type_data::<ReflectInternalPreferences>(type_id)
+                .is_some()
+            {
+                map.set_dyn(value.clone_value());
+            }
+        }
+    }
+
+    world.insert_resource(report);
+    world.insert_resource(map);
+    world.insert_resource(storage);
+
+    let mut profiles = world.resource_mut::<PreferencesProfiles>();
+    let previous = std::mem::replace(&mut profiles.active, active.clone());
+    if let Err(err) = profiles.save() {
+        error!("Error saving profiles: {err}");
+    }
+
+    info!("Switched from profile {previous:?} to {active:?}");
+    world.send_event(PreferencesProfileSwitched { previous, active });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_valid_profile_name;
+    use crate::{
+        Preferences, PreferencesChanged, PreferencesError, PreferencesPlugin, PreferencesProfiles,
+        PreferencesStorageType, RegisterPreferencesExt,
+    };
+    use bevy::prelude::*;
+    use bevy::time::TimePlugin;
+    use std::path::Path;
+
+    #[derive(Reflect, Default, Clone, Debug, PartialEq)]
+    struct Volume {
+        level: u32,
+    }
+
+    #[derive(Resource, Default)]
+    struct ChangedCount(usize);
+
+    fn new_app(path: &Path) -> App {
+        let mut app = App::new();
+        app.add_plugins(TimePlugin)
+            .add_plugins(
+                PreferencesPlugin::persisted_with_app_name("PreferencesTest").with_storage_type(
+                    PreferencesStorageType::FileSystemWithParentDirectory(path.into()),
+                ),
+            )
+            .register_preferences::<Volume>()
+            .init_resource::<ChangedCount>()
+            .add_observer(
+                |_: Trigger<PreferencesChanged<Volume>>, mut count: ResMut<ChangedCount>| {
+                    count.0 += 1;
+                },
+            );
+        app.update();
+        app
+    }
+
+    fn set_level(app: &mut App, level: u32) {
+        app.world_mut()
+            .run_system_cached_with(
+                |In(level): In<u32>, mut volume: Preferences<Volume>| volume.level = level,
+                level,
+            )
+            .unwrap();
+        app.update();
+    }
+
+    fn switch(app: &mut App, name: Option<&str>) {
+        let mut profiles = app.world_mut().resource_mut::<PreferencesProfiles>();
+        profiles.switch(name).unwrap();
+        app.update();
+        app.update();
+    }
+
+    fn level(app: &App) -> u32 {
+        app.world()
+            .resource::<crate::PreferencesResource<Volume>>()
+            .level
+    }
+
+    #[test]
+    fn test_switching_profiles_loads_their_preferences() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut app = new_app(temp_dir.path());
+        set_level(&mut app, 3);
+
+        let mut profiles = app.world_mut().resource_mut::<PreferencesProfiles>();
+        profiles.create("Player 2").unwrap();
+        let changed_before_switch = app.world().resource::<ChangedCount>().0;
+
+        switch(&mut app, Some("Player 2"));
+        assert_eq!(level(&app), 0);
+        assert_eq!(
+            app.world().resource::<ChangedCount>().0,
+            changed_before_switch + 1
+        );
+
+        set_level(&mut app, 7);
+        switch(&mut app, None);
+        assert_eq!(level(&app), 3);
+
+        switch(&mut app, Some("Player 2"));
+        assert_eq!(level(&app), 7);
+        assert!(
+            temp_dir
+                .path()
+                .join("PreferencesTest/profiles/Player 2/preferences.toml")
+                .exists()
+        );
+
+        // The active profile is restored on launch
+        drop(app);
+        let app = new_app(temp_dir.path());
+        let profiles = app.world().resource::<PreferencesProfiles>();
+        assert_eq!(profiles.active(), Some("Player 2"));
+        assert_eq!(level(&app), 7);
+    }
+
+    #[test]
+    fn test_profiles_are_created_copied_renamed_and_deleted() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut app = new_app(temp_dir.path());
+        set_level(&mut app, 5);
+
+        let mut profiles = app.world_mut().resource_mut::<PreferencesProfiles>();
+        profiles.create("Guest").unwrap();
+        profiles.copy(None, "Copy").unwrap();
+        profiles.rename("Copy", "Renamed").unwrap();
+        assert!(matches!(
+            profiles.create("Guest"),
+            Err(PreferencesError::ProfileAlreadyExists(_))
+        ));
+        assert!(matches!(
+            profiles.create("../Guest"),
+            Err(PreferencesError::InvalidProfileName(_))
+        ));
+        assert!(matches!(
+            profiles.switch(Some("Missing")),
+            Err(PreferencesError::ProfileNotFound(_))
+        ));
+
+        profiles.switch(Some("Guest")).unwrap();
+        assert!(matches!(
+            profiles.delete("Guest"),
+            Err(PreferencesError::ProfileIsActive(_))
+        ));
+        profiles.switch(None).unwrap();
+        profiles.delete("Guest").unwrap();
+        assert_eq!(profiles.iter().collect::<Vec<_>>(), ["Renamed"]);
+
+        switch(&mut app, Some("Renamed"));
+        assert_eq!(level(&app), 5);
+    }
+
+    #[test]
+    fn test_profile_names_are_valid_directory_names() {
+        for name in ["Player 2", "Guest", "console", "COM0", "Nullable"] {
+            assert!(is_valid_profile_name(name), "{name}");
+        }
+        for name in [
+            "", " Guest", "../Guest", "a/b", ".hidden", "Guest.", "CON", "nul", "Aux.txt", "COM1",
+            "lpt9",
+        ] {
+            assert!(!is_valid_profile_name(name), "{name}");
+        }
+    }
+}
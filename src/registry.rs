@@ -1,9 +1,31 @@
+use crate::changes::{PreferencesChanged, PreferencesSnapshot};
+use crate::components::PersistComponentsPlugin;
+#[cfg(doc)]
+use crate::components::{PersistId, PersistedComponents};
+use crate::defaults::PreferencesDefaults;
+#[cfg(doc)]
+use crate::keyed::KeyedPreferences;
+use crate::keyed::KeyedPreferencesPlugin;
+use crate::profiles::PreferencesProfileSwitched;
+use crate::report::{PreferencesLoadReport, PreferencesLoadSource};
 use crate::resource::PreferencesResource;
 use crate::serializable_map::PreferencesSerializableMap;
-use crate::{PreferencesSet, PreferencesType, ReflectPreferences};
+use crate::staging::{
+    PreferencesReverted, PreferencesStaging, has_pending_confirmation, tick_pending_confirmation,
+};
+#[cfg(feature = "state")]
+use crate::state::PersistStatePlugin;
+use crate::validation::{
+    PreferencesValidationFailed, PreferencesValidationStrategy, PreferencesValidators,
+};
+use crate::{PreferencesSet, PreferencesType, ReflectPreferences, clone_preferences};
+use bevy::ecs::system::{SystemId, SystemParam};
 use bevy::prelude::*;
-use bevy::reflect::{Reflectable, TypeInfo, TypeRegistration, TypeRegistry};
+use bevy::reflect::{GetTypeRegistration, Reflectable, TypeInfo, TypeRegistration, TypeRegistry};
+#[cfg(feature = "state")]
+use bevy::state::state::FreelyMutableState;
 use std::any::TypeId;
+use std::marker::PhantomData;
 use std::sync::Mutex;
 
 pub(crate) struct PreferencesRegistryData<'a> {
@@ -65,10 +87,23 @@ impl<'a> PreferencesRegistryData<'a> {
     }
 
     pub fn convert_to_concrete_type(&self, value: Box<dyn PartialReflect>) -> Box<dyn Reflect> {
+        let type_path = value.reflect_type_path().to_owned();
+        self.try_convert_to_concrete_type(value)
+            .map(|(value, _)| value)
+            .unwrap_or_else(|| panic!("Error using ReflectFromReflect:\nTypePath: {type_path}"))
+    }
+
+    /// Converts a value into its concrete type, returning how the value was converted.
+    /// If the value doesn't match the type, but it can be applied into its default value,
+    /// [`PreferencesLoadSource::Migrated`] is returned.
+    pub fn try_convert_to_concrete_type(
+        &self,
+        value: Box<dyn PartialReflect>,
+    ) -> Option<(Box<dyn Reflect>, PreferencesLoadSource)> {
         let value = match value.try_into_reflect() {
             Ok(value) => {
                 if value.as_any().type_id() == self.type_id {
-                    return value;
+                    return Some((value, PreferencesLoadSource::Loaded));
                 }
                 value.into_partial_reflect()
             }
@@ -77,31 +112,24 @@ impl<'a> PreferencesRegistryData<'a> {
 
         let type_path = value.reflect_type_path();
 
-        self.from_reflect
-            .from_reflect(&*value)
-            .or_else(|| {
-                debug!(
-                    "FromReflect did not work for type :{type_path}\nValue:{:#?}",
-                    &value
-                );
+        if let Some(value) = self.from_reflect.from_reflect(&*value) {
+            return Some((value, PreferencesLoadSource::Loaded));
+        }
 
-                if let Some(reflect_default) = self.default {
-                    let mut default_value = reflect_default.default();
-                    match default_value.try_apply(value.as_partial_reflect()) {
-                        Ok(_) => Some(default_value),
-                        Err(err) => {
-                            error!("try_apply did not work for type: {type_path}: {err}");
-                            None
-                        }
-                    }
-                } else {
-                    None
-                }
-            })
-            .unwrap_or_else(|| {
-                // TODO: Return None
-                panic!("Error using ReflectFromReflect:\nTypePath: {type_path}\nValue: {value:#?}")
-            })
+        debug!(
+            "FromReflect did not work for type :{type_path}\nValue:{:#?}",
+            &value
+        );
+
+        let reflect_default = self.default?;
+        let mut default_value = reflect_default.default();
+        match default_value.try_apply(value.as_partial_reflect()) {
+            Ok(_) => Some((default_value, PreferencesLoadSource::Migrated)),
+            Err(err) => {
+                error!("try_apply did not work for type: {type_path}: {err}");
+                None
+            }
+        }
     }
 }
 
@@ -120,6 +148,183 @@ pub trait RegisterPreferencesExt {
     fn register_preferences_with_default_value<T>(&mut self, default_value: T) -> &mut Self
     where
         T: Reflectable + PreferencesType;
+
+    /// Registers a type as a [`PreferencesType`] type, whose default value is computed by `system`
+    /// at startup, so it can depend on runtime information.
+    ///
+    /// The system runs once, unless the default value has been overridden. Its result is used
+    /// when nothing is loaded from storage, and by [`crate::Preferences::reset`].
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_simple_preferences::*;
+    /// #[derive(Reflect)]
+    /// struct PerformancePreferences {
+    ///     worker_threads: usize,
+    /// }
+    ///
+    /// App::new().register_preferences_with_default_system(|| PerformancePreferences {
+    ///     worker_threads: std::thread::available_parallelism().map_or(1, |n| n.get()),
+    /// });
+    /// ```
+    fn register_preferences_with_default_system<T, M>(
+        &mut self,
+        system: impl IntoSystem<(), T, M> + 'static,
+    ) -> &mut Self
+    where
+        T: Reflectable + PreferencesType;
+
+    /// Adds a custom validator to the preferences of type `T`.
+    /// Validators run when the preferences are loaded and every time they change, before being stored.
+    /// Changes are validated at the end of the frame they're made in, in [`PreferencesSet::SetReflectMapValues`],
+    /// so systems running later in the same frame may still see the invalid value.
+    ///
+    /// A validator can fix the value itself, and return an error if the value is not valid,
+    /// in which case the [`PreferencesValidationStrategy`] is used.
+    /// Fields can also be validated declaratively using [`crate::PreferencesRange`],
+    /// [`crate::AllowedVariants`] and [`crate::NonEmpty`] attributes.
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_simple_preferences::*;
+    /// #[derive(Reflect, Default)]
+    /// struct WindowPreferences {
+    ///     width: u32,
+    ///     height: u32,
+    /// }
+    ///
+    /// App::new()
+    ///     .register_preferences::<WindowPreferences>()
+    ///     .add_preferences_validator(|window: &mut WindowPreferences| {
+    ///         if window.width == 0 || window.height == 0 {
+    ///             return Err(format!("Invalid resolution {}x{}", window.width, window.height));
+    ///         }
+    ///         Ok(())
+    ///     });
+    /// ```
+    fn add_preferences_validator<T>(
+        &mut self,
+        validator: impl Fn(&mut T) -> Result<(), String> + Send + Sync + 'static,
+    ) -> &mut Self
+    where
+        T: PreferencesType;
+
+    /// Sets what to do when the preferences of type `T` are not valid.
+    /// [`PreferencesValidationStrategy::Clamp`] is used by default.
+    fn set_preferences_validation_strategy<T>(
+        &mut self,
+        strategy: PreferencesValidationStrategy,
+    ) -> &mut Self
+    where
+        T: PreferencesType;
+
+    /// Overrides the default value of a [`PreferencesType`] type registered by another plugin,
+    /// no matter if that plugin is added before or after calling this method.
+    /// It's meant to be used by final applications, not libraries.
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_simple_preferences::*;
+    /// # #[derive(Reflect, Default)]
+    /// # struct AudioPreferences {
+    /// #    master_volume: f32,
+    /// # }
+    /// # struct AudioPlugin;
+    /// # impl Plugin for AudioPlugin {
+    /// #     fn build(&self, app: &mut App) {
+    /// #         app.register_preferences::<AudioPreferences>();
+    /// #     }
+    /// # }
+    /// App::new()
+    ///     .override_preferences_default(AudioPreferences { master_volume: 0.8 })
+    ///     .add_plugins(AudioPlugin);
+    /// ```
+    fn override_preferences_default<T>(&mut self, default_value: T) -> &mut Self
+    where
+        T: PreferencesType;
+
+    /// Persists an existing [`Resource`] as preferences, without wrapping it in [`PreferencesResource`].
+    ///
+    /// The resource must exist at startup, and its value at that point is used as the default value.
+    /// The stored value is loaded into the resource, and every change to the resource is stored,
+    /// sending [`PreferencesChanged`] like any other preferences.
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_simple_preferences::*;
+    /// App::new()
+    ///     .insert_resource(ClearColor(Color::BLACK))
+    ///     .persist_resource::<ClearColor>();
+    /// ```
+    #[track_caller]
+    fn persist_resource<R>(&mut self) -> &mut Self
+    where
+        R: Resource + Reflectable + PreferencesType;
+
+    /// Persists the components of type `C` of every entity with a [`PersistId`],
+    /// storing them in [`PersistedComponents`] by their id.
+    ///
+    /// The stored component is inserted into entities as soon as they are spawned with a matching [`PersistId`],
+    /// and every change to the component is stored afterward. After switching profiles,
+    /// the components stored by the new profile are inserted into the entities that already exist.
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_simple_preferences::*;
+    /// App::new()
+    ///     .persist_components::<Transform>()
+    ///     .add_systems(Startup, |mut commands: Commands| {
+    ///         commands.spawn((Transform::default(), PersistId("editor_camera".into())));
+    ///     });
+    /// ```
+    #[track_caller]
+    fn persist_components<C>(&mut self) -> &mut Self
+    where
+        C: Component + Reflectable + PreferencesType;
+
+    /// Persists the current [`States`](bevy::state::state::States) of type `S`,
+    /// storing it in [`PersistedStates`](crate::PersistedStates) by its variant name after every transition.
+    ///
+    /// The stored state replaces the initial state before the first transition,
+    /// so `OnEnter` only runs for the stored state. The state needs to be initialized with
+    /// `init_state` or `insert_state`, and only unit variants can be persisted.
+    ///
+    /// Requires the `state` feature.
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_simple_preferences::*;
+    /// #[derive(States, Reflect, Clone, Debug, Default, PartialEq, Eq, Hash)]
+    /// enum MenuTab {
+    ///     #[default]
+    ///     General,
+    ///     Audio,
+    /// }
+    ///
+    /// App::new()
+    ///     .add_plugins(bevy::state::app::StatesPlugin)
+    ///     .init_state::<MenuTab>()
+    ///     .persist_state::<MenuTab>();
+    /// ```
+    #[cfg(feature = "state")]
+    #[track_caller]
+    fn persist_state<S>(&mut self) -> &mut Self
+    where
+        S: FreelyMutableState + Reflectable + PreferencesType;
+
+    /// Registers preferences that store a value of type `T` for every key of type `K`,
+    /// like per-gamepad settings keyed by device name. They are accessed using [`KeyedPreferences`],
+    /// and new keys use [`Default::default`] as their value.
+    #[track_caller]
+    fn register_keyed_preferences<K, T>(&mut self) -> &mut Self
+    where
+        K: Reflectable + PreferencesType + Ord + Clone,
+        T: Reflectable + PreferencesType + Default;
+
+    /// Registers preferences that store a value of type `T` for every key of type `K`.
+    /// New keys use the specified value. See [`RegisterPreferencesExt::register_keyed_preferences`].
+    #[track_caller]
+    fn register_keyed_preferences_with_default_value<K, T>(
+        &mut self,
+        default_value: T,
+    ) -> &mut Self
+    where
+        K: Reflectable + PreferencesType + Ord + Clone,
+        T: Reflectable + PreferencesType;
 }
 
 impl RegisterPreferencesExt for App {
@@ -153,6 +358,136 @@ impl RegisterPreferencesExt for App {
         self.add_plugins(RegisteredPreferencesPlugin::new(default_value));
         self
     }
+
+    fn register_preferences_with_default_system<T, M>(
+        &mut self,
+        system: impl IntoSystem<(), T, M> + 'static,
+    ) -> &mut Self
+    where
+        T: Reflectable + PreferencesType,
+    {
+        self.register_type::<T>()
+            .register_type_data::<T, ReflectPreferences>()
+            .register_type_data::<T, ReflectFromReflect>();
+
+        self.register_type::<PreferencesResource<T>>();
+
+        self.add_plugins(RegisteredPreferencesPlugin::<T>::deferred());
+
+        let system_id = self.world_mut().register_system(system);
+        self.add_systems(
+            PreStartup,
+            compute_default_value::<T>(system_id)
+                .in_set(PreferencesSet::AssignResources)
+                .before(validate_default_value::<T>),
+        );
+        self
+    }
+
+    fn add_preferences_validator<T>(
+        &mut self,
+        validator: impl Fn(&mut T) -> Result<(), String> + Send + Sync + 'static,
+    ) -> &mut Self
+    where
+        T: PreferencesType,
+    {
+        self.world_mut()
+            .get_resource_or_init::<PreferencesValidators<T>>()
+            .add(validator);
+        self
+    }
+
+    fn set_preferences_validation_strategy<T>(
+        &mut self,
+        strategy: PreferencesValidationStrategy,
+    ) -> &mut Self
+    where
+        T: PreferencesType,
+    {
+        self.world_mut()
+            .get_resource_or_init::<PreferencesValidators<T>>()
+            .strategy = strategy;
+        self
+    }
+
+    fn override_preferences_default<T>(&mut self, default_value: T) -> &mut Self
+    where
+        T: PreferencesType,
+    {
+        self.world_mut()
+            .get_resource_or_init::<PreferencesDefaults>()
+            .insert_override(default_value);
+        self
+    }
+
+    #[track_caller]
+    fn persist_resource<R>(&mut self) -> &mut Self
+    where
+        R: Resource + Reflectable + PreferencesType,
+    {
+        self.register_type::<R>()
+            .register_type_data::<R, ReflectFromReflect>();
+
+        self.world()
+            .resource::<AppTypeRegistry>()
+            .write()
+            .get_mut(TypeId::of::<R>())
+            .expect("Type is registered")
+            .insert(ReflectPreferences::from_resource::<R>());
+
+        self.add_plugins(PersistedResourcePlugin::<R>(PhantomData));
+        self
+    }
+
+    #[track_caller]
+    fn persist_components<C>(&mut self) -> &mut Self
+    where
+        C: Component + Reflectable + PreferencesType,
+    {
+        self.add_plugins(PersistComponentsPlugin::<C>::default());
+        self
+    }
+
+    #[cfg(feature = "state")]
+    #[track_caller]
+    fn persist_state<S>(&mut self) -> &mut Self
+    where
+        S: FreelyMutableState + Reflectable + PreferencesType,
+    {
+        self.add_plugins(PersistStatePlugin::<S>::default());
+        self
+    }
+
+    #[track_caller]
+    fn register_keyed_preferences<K, T>(&mut self) -> &mut Self
+    where
+        K: Reflectable + PreferencesType + Ord + Clone,
+        T: Reflectable + PreferencesType + Default,
+    {
+        self.register_keyed_preferences_with_default_value::<K, T>(T::default())
+    }
+
+    #[track_caller]
+    fn register_keyed_preferences_with_default_value<K, T>(&mut self, default_value: T) -> &mut Self
+    where
+        K: Reflectable + PreferencesType + Ord + Clone,
+        T: Reflectable + PreferencesType,
+    {
+        self.add_plugins(KeyedPreferencesPlugin::<K, T>::new(default_value));
+        self
+    }
+}
+
+pub(crate) trait RegisterMapTypesExt {
+    /// Registers the key and value types of a `BTreeMap<K, V>` stored in preferences,
+    /// since the map doesn't register them, and they're needed to deserialize it.
+    fn register_map_types<K: GetTypeRegistration, V: GetTypeRegistration>(&mut self) -> &mut Self;
+}
+
+impl RegisterMapTypesExt for App {
+    fn register_map_types<K: GetTypeRegistration, V: GetTypeRegistration>(&mut self) -> &mut Self {
+        self.register_type::<K>().register_type::<V>()
+    }
 }
 
 struct RegisteredPreferencesPlugin<T> {
@@ -165,6 +500,13 @@ impl<T> RegisteredPreferencesPlugin<T> {
             default_value: Mutex::new(Some(value)),
         }
     }
+
+    /// The default value is inserted later, by [`compute_default_value`].
+    pub fn deferred() -> Self {
+        Self {
+            default_value: Mutex::new(None),
+        }
+    }
 }
 
 impl<T> Plugin for RegisteredPreferencesPlugin<T>
@@ -172,26 +514,175 @@ where
     T: PreferencesType + Reflectable,
 {
     fn build(&self, app: &mut App) {
-        let initial_value = {
+        let default_value = {
             let mut lock = self.default_value.try_lock().unwrap();
-            lock.take().expect("Cannot build Plugin more than once")
+            lock.take()
         };
+        let mut defaults = app
+            .world_mut()
+            .get_resource_or_init::<PreferencesDefaults>();
+        if let Some(default_value) = default_value {
+            defaults.insert(default_value);
+        }
+
         app.register_type::<PreferencesResource<T>>()
+            .init_resource::<PreferencesLoadReport>()
             .add_systems(
                 PreStartup,
-                Self::assign_initial_value(initial_value).in_set(PreferencesSet::AssignResources),
+                (validate_default_value::<T>, Self::assign_initial_value)
+                    .chain()
+                    .in_set(PreferencesSet::AssignResources),
+            )
+            .add_event::<PreferencesChanged<T>>()
+            .init_resource::<PreferencesValidators<T>>()
+            .add_event::<PreferencesValidationFailed<T>>()
+            .init_resource::<PreferencesStaging<T>>()
+            .add_event::<PreferencesReverted<T>>()
+            .add_event::<PreferencesProfileSwitched>()
+            .add_systems(
+                PreUpdate,
+                Self::reload_value
+                    .in_set(PreferencesSet::AssignResources)
+                    .run_if(on_event::<PreferencesProfileSwitched>),
             )
             .add_systems(
                 Last,
-                Self::set_reflect_map_value
+                (
+                    tick_pending_confirmation::<T>
+                        .before(PreferencesSet::SetReflectMapValues)
+                        .run_if(has_pending_confirmation::<T>),
+                    Self::commit_changes
+                        .in_set(PreferencesSet::SetReflectMapValues)
+                        .run_if(preferences_changed::<T>),
+                ),
+            );
+    }
+}
+
+struct PersistedResourcePlugin<R>(PhantomData<R>);
+
+impl<R> Plugin for PersistedResourcePlugin<R>
+where
+    R: Resource + PreferencesType,
+{
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PreferencesDefaults>()
+            .init_resource::<PreferencesLoadReport>()
+            .add_systems(
+                PreStartup,
+                (
+                    Self::capture_default_value,
+                    validate_default_value::<R>,
+                    Self::assign_initial_value,
+                )
+                    .chain()
+                    .in_set(PreferencesSet::AssignResources),
+            )
+            .add_event::<PreferencesChanged<R>>()
+            .init_resource::<PreferencesValidators<R>>()
+            .add_event::<PreferencesValidationFailed<R>>()
+            .add_event::<PreferencesProfileSwitched>()
+            .add_systems(
+                PreUpdate,
+                Self::reload_value
+                    .in_set(PreferencesSet::AssignResources)
+                    .run_if(on_event::<PreferencesProfileSwitched>),
+            )
+            .add_systems(
+                Last,
+                Self::commit_changes
                     .in_set(PreferencesSet::SetReflectMapValues)
-                    .run_if(
-                        preferences_changed::<T>.and(resource_exists::<PreferencesSerializableMap>),
-                    ),
+                    .run_if(resource_exists_and_changed::<R>)
+                    .run_if(resource_exists::<PreferencesSnapshot<R>>),
             );
     }
 }
 
+impl<R> PersistedResourcePlugin<R>
+where
+    R: Resource + PreferencesType,
+{
+    fn capture_default_value(resource: Option<Res<R>>, mut defaults: ResMut<PreferencesDefaults>) {
+        match resource {
+            Some(resource) => defaults.insert(clone_preferences(&*resource)),
+            None => warn!(
+                "Resource {} does not exist at startup, so it's not persisted",
+                R::short_type_path()
+            ),
+        }
+    }
+
+    fn assign_initial_value(resource: Option<ResMut<R>>, mut loader: PreferencesLoader<R>) {
+        let Some(mut resource) = resource else {
+            return;
+        };
+
+        let value = loader.load();
+        if !resource.reflect_partial_eq(&value).unwrap_or(false) {
+            *resource = value;
+        }
+    }
+
+    // Assigns the value of the new profile after switching
+    fn reload_value(resource: Option<ResMut<R>>, mut loader: PreferencesLoader<R>) {
+        let Some(mut resource) = resource else {
+            return;
+        };
+
+        let value = loader.load_value();
+        if !resource.reflect_partial_eq(&value).unwrap_or(false) {
+            *resource = value;
+        }
+    }
+
+    fn commit_changes(resource: ResMut<R>, mut committer: PreferencesCommitter<R>) {
+        committer.commit(resource.into());
+    }
+}
+
+// Runs the system that computes the default value, unless the default value is overridden
+fn compute_default_value<T: PreferencesType>(system_id: SystemId<(), T>) -> impl FnMut(&mut World) {
+    move |world| {
+        let is_overridden = world
+            .get_resource::<PreferencesDefaults>()
+            .is_some_and(|defaults| defaults.is_overridden::<T>());
+        if is_overridden {
+            return;
+        }
+
+        let default_value = world.run_system(system_id).unwrap_or_else(|err| {
+            panic!(
+                "Error computing the default value of preferences {}: {err}",
+                T::short_type_path()
+            )
+        });
+
+        world
+            .resource_mut::<PreferencesDefaults>()
+            .insert(default_value);
+    }
+}
+
+// Invalid defaults can't be used to fix invalid values, so they're reported once at startup
+fn validate_default_value<T: PreferencesType>(
+    defaults: Res<PreferencesDefaults>,
+    validators: Res<PreferencesValidators<T>>,
+    mut validation_failed: EventWriter<PreferencesValidationFailed<T>>,
+) {
+    let Some(default_value) = defaults.get::<T>() else {
+        return;
+    };
+
+    let mut value = clone_preferences(default_value);
+    if let Some(failed) = validators.validate(&mut value, default_value, None) {
+        error!(
+            "Default value of preferences {} is invalid",
+            T::short_type_path()
+        );
+        validation_failed.send(failed);
+    }
+}
+
 // Detect if preferences have changed
 fn preferences_changed<T: PreferencesType>(
     preferences: Option<Res<PreferencesResource<T>>>,
@@ -204,36 +695,216 @@ where
     T: PreferencesType,
 {
     fn assign_initial_value(
-        default_value: T,
-    ) -> impl FnMut(Commands, Option<ResMut<PreferencesSerializableMap>>) {
-        let mut default_value = Some(default_value);
-        move |mut commands, storage_map| {
-            let stored_value: Option<T> =
-                storage_map.and_then(|mut storage_map| storage_map.take());
-
-            let value = stored_value.unwrap_or_else(|| {
-                default_value
-                    .take()
-                    .expect("This system should not be executed more than once")
-            });
-            commands.insert_resource(PreferencesResource::new(value));
+        existing_value: Option<Res<PreferencesResource<T>>>,
+        mut loader: PreferencesLoader<T>,
+    ) {
+        if existing_value.is_some() {
+            // The existing value is committed like any other change.
+            loader.keep_existing();
+            return;
+        }
+
+        let value = loader.load();
+        loader
+            .commands
+            .insert_resource(PreferencesResource::new(value));
+    }
+
+    // Assigns the value of the new profile after switching
+    fn reload_value(
+        preferences: Option<ResMut<PreferencesResource<T>>>,
+        mut loader: PreferencesLoader<T>,
+    ) {
+        let Some(mut preferences) = preferences else {
+            return;
+        };
+
+        let value = loader.load_value();
+        if !(**preferences).reflect_partial_eq(&value).unwrap_or(false) {
+            **preferences = value;
         }
     }
 
-    fn set_reflect_map_value(
-        value: Res<PreferencesResource<T>>,
-        mut storage_map: ResMut<PreferencesSerializableMap>,
+    fn commit_changes(
+        value: ResMut<PreferencesResource<T>>,
+        mut committer: PreferencesCommitter<T>,
     ) {
-        let cloned_value = T::from_reflect(&**value).expect("Error while trying to clone value");
-        storage_map.set(cloned_value);
+        committer.commit(value.map_unchanged(|value| &mut **value));
+    }
+}
+
+/// Loads the initial value of preferences from the storage map, falling back to the registered default.
+#[derive(SystemParam)]
+pub(crate) struct PreferencesLoader<'w, 's, T: PreferencesType> {
+    commands: Commands<'w, 's>,
+    storage_map: Option<ResMut<'w, PreferencesSerializableMap>>,
+    defaults: Res<'w, PreferencesDefaults>,
+    report: ResMut<'w, PreferencesLoadReport>,
+    validators: Res<'w, PreferencesValidators<T>>,
+    validation_failed: EventWriter<'w, PreferencesValidationFailed<T>>,
+}
+
+impl<T: PreferencesType> PreferencesLoader<'_, '_, T> {
+    fn registered_default(&self) -> &T {
+        self.defaults
+            .get::<T>()
+            .expect("Default value is registered with the preferences")
+    }
+
+    fn stored_value(&mut self) -> (Option<T>, Option<PreferencesLoadSource>) {
+        let default_value = clone_preferences(self.registered_default());
+
+        let Some(storage_map) = self.storage_map.as_mut() else {
+            return (None, None);
+        };
+        storage_map
+            .bypass_change_detection()
+            .resolve_sparse(&default_value);
+
+        (
+            storage_map
+                .get::<T>()
+                .and_then(|value| T::from_reflect(value)),
+            storage_map.load_source::<T>(),
+        )
+    }
+
+    /// Records that the value was set before loading, and takes the snapshot it's compared against.
+    pub(crate) fn keep_existing(&mut self) {
+        self.report.record::<T>(PreferencesLoadSource::Overridden);
+        let (stored_value, _) = self.stored_value();
+        let snapshot = stored_value.unwrap_or_else(|| clone_preferences(self.registered_default()));
+        self.commands
+            .insert_resource(PreferencesSnapshot::new(snapshot));
+    }
+
+    /// Returns the validated stored value, or the default value if nothing is stored,
+    /// and takes the snapshot it's compared against.
+    pub(crate) fn load(&mut self) -> T {
+        let value = self.load_value();
+        self.commands
+            .insert_resource(PreferencesSnapshot::new(clone_preferences(&value)));
+        value
+    }
+
+    /// Returns the validated stored value, or the default value if nothing is stored,
+    /// keeping the snapshot, so assigning it is committed like any other change.
+    pub(crate) fn load_value(&mut self) -> T {
+        let (stored_value, source) = self.stored_value();
+
+        let source = match source {
+            Some(source) => source,
+            None if self.report.load_error().is_some() => PreferencesLoadSource::DefaultedError,
+            None => PreferencesLoadSource::DefaultedMissing,
+        };
+        self.report.record::<T>(source);
+
+        let Some(mut value) = stored_value else {
+            let default_value = clone_preferences(self.registered_default());
+            // The map keeps the last persisted value, so no-op writes can be detected later on.
+            if let Some(storage_map) = self.storage_map.as_mut() {
+                storage_map
+                    .bypass_change_detection()
+                    .set(clone_preferences(&default_value));
+            }
+            // The default value is validated once at startup
+            return default_value;
+        };
+
+        let registered_default = self
+            .defaults
+            .get::<T>()
+            .expect("Default value is registered with the preferences");
+        if let Some(failed) = self
+            .validators
+            .validate(&mut value, registered_default, None)
+        {
+            // The fixed value is stored, replacing the invalid one
+            if let Some(storage_map) = self.storage_map.as_mut() {
+                storage_map.set(clone_preferences(&value));
+            }
+            self.validation_failed.send(failed);
+        }
+
+        value
+    }
+}
+
+/// Validates changed preferences and stores them, sending [`PreferencesChanged`] on real changes.
+#[derive(SystemParam)]
+pub(crate) struct PreferencesCommitter<'w, 's, T: PreferencesType> {
+    commands: Commands<'w, 's>,
+    snapshot: ResMut<'w, PreferencesSnapshot<T>>,
+    storage_map: Option<ResMut<'w, PreferencesSerializableMap>>,
+    preferences_changed: EventWriter<'w, PreferencesChanged<T>>,
+    defaults: Res<'w, PreferencesDefaults>,
+    validators: Res<'w, PreferencesValidators<T>>,
+    validation_failed: EventWriter<'w, PreferencesValidationFailed<T>>,
+    staging: Option<Res<'w, PreferencesStaging<T>>>,
+}
+
+impl<T: PreferencesType> PreferencesCommitter<'_, '_, T> {
+    pub(crate) fn commit(&mut self, mut value: Mut<T>) {
+        let default_value = self
+            .defaults
+            .get::<T>()
+            .expect("Default value is registered with the preferences");
+
+        // `ResMut` marks the preferences as changed even if nothing was modified,
+        // so the value is compared against the last committed one.
+        let is_unchanged =
+            |snapshot: &T, value: &T| snapshot.reflect_partial_eq(value).unwrap_or(false);
+
+        if !is_unchanged(&self.snapshot, &value) {
+            // Invalid values are fixed before being committed, whenever possible
+            if let Some(failed) = self.validators.validate(
+                value.bypass_change_detection(),
+                default_value,
+                Some(&**self.snapshot),
+            ) {
+                self.validation_failed.send(failed);
+            }
+        }
+
+        if !is_unchanged(&self.snapshot, &value) {
+            let old = self.snapshot.replace(clone_preferences(&*value));
+            let event = PreferencesChanged::new(old, clone_preferences(&*value));
+            self.commands.trigger(event.clone());
+            self.preferences_changed.send(event);
+        }
+
+        self.store(&value);
+    }
+
+    // Applied preferences that are pending confirmation are not stored until they're confirmed
+    fn store(&mut self, value: &T) {
+        let Some(storage_map) = self.storage_map.as_mut() else {
+            return;
+        };
+        let value = self
+            .staging
+            .as_ref()
+            .and_then(|staging| staging.confirmed_value())
+            .unwrap_or(value);
+
+        let is_stored = storage_map
+            .get::<T>()
+            .is_some_and(|stored| stored.reflect_partial_eq(value).unwrap_or(false));
+        if !is_stored {
+            storage_map.set(clone_preferences(value));
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::serializable_map::PreferencesSerializableMap;
-    use crate::{Preferences, PreferencesSet, RegisterPreferencesExt};
+    use crate::{
+        Preferences, PreferencesChanged, PreferencesLoadReport, PreferencesLoadSource,
+        PreferencesResource, PreferencesSet, PreferencesWorldExt, RegisterPreferencesExt,
+    };
     use bevy::prelude::*;
+    use bevy::reflect::{DynamicStruct, Typed};
 
     #[derive(Reflect)]
     struct MyPreferences {
@@ -248,6 +919,43 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_map_types_round_trip_through_serde() {
+        use super::RegisterMapTypesExt;
+        use bevy::reflect::serde::{TypedReflectDeserializer, TypedReflectSerializer};
+        use serde::de::DeserializeSeed;
+        use std::collections::BTreeMap;
+
+        #[derive(Reflect, Clone, Default)]
+        struct Entry {
+            value: u32,
+        }
+
+        #[derive(Reflect, Default)]
+        struct MapPreferences {
+            entries: BTreeMap<String, Entry>,
+        }
+
+        let mut app = App::new();
+        app.register_map_types::<String, Entry>()
+            .register_preferences::<MapPreferences>();
+        let registry = app.world().resource::<AppTypeRegistry>().read();
+        let preferences = MapPreferences {
+            entries: [("first".to_string(), Entry { value: 4 })].into(),
+        };
+
+        let output =
+            serde_json::to_string(&TypedReflectSerializer::new(&preferences, &registry)).unwrap();
+        let registration = registry
+            .get(std::any::TypeId::of::<MapPreferences>())
+            .unwrap();
+        let value = TypedReflectDeserializer::new(registration, &registry)
+            .deserialize(&mut serde_json::Deserializer::from_str(&output))
+            .unwrap();
+
+        assert!(value.reflect_partial_eq(&preferences).unwrap());
+    }
+
     #[test]
     fn test_register_preferences_using_default() {
         App::new()
@@ -291,6 +999,220 @@ mod tests {
             .run();
     }
 
+    #[test]
+    fn test_override_default_before_and_after_registering() {
+        let overridden = || MyPreferences {
+            value: "OverriddenValue",
+        };
+
+        App::new()
+            .override_preferences_default(overridden())
+            .register_preferences::<MyPreferences>()
+            .add_systems(Update, |pref: Preferences<MyPreferences>| {
+                assert_eq!(pref.value, "OverriddenValue");
+            })
+            .run();
+
+        App::new()
+            .register_preferences::<MyPreferences>()
+            .override_preferences_default(overridden())
+            .add_systems(Update, |pref: Preferences<MyPreferences>| {
+                assert_eq!(pref.value, "OverriddenValue");
+                assert_eq!(pref.default_value().value, "OverriddenValue");
+            })
+            .run();
+    }
+
+    #[derive(Resource)]
+    struct DetectedValue(&'static str);
+
+    #[test]
+    fn test_default_system_computes_default_value() {
+        App::new()
+            .insert_resource(DetectedValue("ComputedValue"))
+            .register_preferences_with_default_system(|detected: Res<DetectedValue>| {
+                MyPreferences { value: detected.0 }
+            })
+            .add_systems(Update, |pref: Preferences<MyPreferences>| {
+                assert_eq!(pref.value, "ComputedValue");
+                assert!(pref.is_default());
+            })
+            .run();
+
+        for sparse in [false, true] {
+            let mut app = App::new();
+            app.register_preferences_with_default_system(|| MyPreferences {
+                value: "ComputedValue",
+            });
+
+            let mut reflect_map = {
+                let type_registry_arc = app.world().resource::<AppTypeRegistry>().0.clone();
+                if sparse {
+                    PreferencesSerializableMap::empty_sparse(type_registry_arc)
+                } else {
+                    PreferencesSerializableMap::empty(type_registry_arc)
+                }
+            };
+            reflect_map.set(MyPreferences { value: "Stored" });
+
+            app.insert_resource(reflect_map)
+                .add_systems(Update, |mut pref: Preferences<MyPreferences>| {
+                    assert_eq!(pref.value, "Stored");
+                    assert!(!pref.is_default());
+                    assert_eq!(pref.default_value().value, "ComputedValue");
+                    pref.reset();
+                    assert_eq!(pref.value, "ComputedValue");
+                })
+                .run();
+        }
+    }
+
+    #[test]
+    fn test_load_report_records_defaulted_missing() {
+        App::new()
+            .register_preferences::<MyPreferences>()
+            .init_resource::<PreferencesSerializableMap>()
+            .add_systems(Startup, |report: Res<PreferencesLoadReport>| {
+                assert_eq!(
+                    report.get::<MyPreferences>(),
+                    Some(PreferencesLoadSource::DefaultedMissing)
+                );
+            })
+            .run();
+    }
+
+    #[test]
+    fn test_load_report_records_loaded_and_migrated() {
+        #[derive(Reflect, Default)]
+        struct Migrated {
+            old_field: u32,
+            new_field: u32,
+        }
+
+        let mut app = App::new();
+        app.register_preferences::<MyPreferences>()
+            .register_preferences::<Migrated>();
+
+        let reflect_map = {
+            let type_registry_arc = app.world().resource::<AppTypeRegistry>().0.clone();
+
+            let mut old_migrated = DynamicStruct::default();
+            old_migrated.set_represented_type(Some(<Migrated as Typed>::type_info()));
+            old_migrated.insert("old_field", 7u32);
+
+            PreferencesSerializableMap::from_dynamic_values(
+                [
+                    (
+                        "MyPreferences".to_string(),
+                        Box::new(MyPreferences { value: "Stored" }).into_partial_reflect(),
+                    ),
+                    (
+                        "Migrated".to_string(),
+                        Box::new(old_migrated).into_partial_reflect(),
+                    ),
+                ],
+                type_registry_arc,
+            )
+        };
+
+        app.insert_resource(reflect_map)
+            .add_systems(
+                Startup,
+                |report: Res<PreferencesLoadReport>, migrated: Preferences<Migrated>| {
+                    assert_eq!(
+                        report.get::<MyPreferences>(),
+                        Some(PreferencesLoadSource::Loaded)
+                    );
+                    assert_eq!(
+                        report.get::<Migrated>(),
+                        Some(PreferencesLoadSource::Migrated)
+                    );
+                    assert_eq!(migrated.old_field, 7);
+                },
+            )
+            .run();
+    }
+
+    #[test]
+    fn test_load_report_records_overridden() {
+        App::new()
+            .register_preferences::<MyPreferences>()
+            .insert_resource(PreferencesResource::new(MyPreferences {
+                value: "Overridden",
+            }))
+            .add_systems(
+                Startup,
+                |report: Res<PreferencesLoadReport>, pref: Preferences<MyPreferences>| {
+                    assert_eq!(
+                        report.get::<MyPreferences>(),
+                        Some(PreferencesLoadSource::Overridden)
+                    );
+                    assert_eq!(pref.value, "Overridden");
+                },
+            )
+            .run();
+    }
+
+    #[test]
+    fn test_no_op_writes_do_not_change_reflect_map() {
+        let mut app = App::new();
+        app.register_preferences::<MyPreferences>()
+            .init_resource::<PreferencesSerializableMap>()
+            .add_systems(Update, |mut pref: Preferences<MyPreferences>| {
+                pref.value = "DefaultValue";
+            });
+
+        app.update();
+        let last_changed = app
+            .world()
+            .resource_ref::<PreferencesSerializableMap>()
+            .last_changed();
+
+        app.update();
+        let map = app.world().resource_ref::<PreferencesSerializableMap>();
+        assert_eq!(map.last_changed(), last_changed);
+        assert_eq!(map.get::<MyPreferences>().unwrap().value, "DefaultValue");
+    }
+
+    #[derive(Resource, Default)]
+    struct ChangedValues(Vec<(&'static str, &'static str)>);
+
+    #[test]
+    fn test_preferences_changed_is_sent_and_triggered_on_real_changes() {
+        let mut app = App::new();
+        app.register_preferences::<MyPreferences>()
+            .init_resource::<ChangedValues>()
+            .add_systems(Update, |mut pref: Preferences<MyPreferences>| {
+                pref.value = "ValueFromSystem";
+            })
+            .add_observer(
+                |trigger: Trigger<PreferencesChanged<MyPreferences>>,
+                 mut changed_values: ResMut<ChangedValues>| {
+                    changed_values
+                        .0
+                        .push((trigger.old.value, trigger.new.value));
+                },
+            );
+
+        app.update();
+        app.update();
+
+        assert_eq!(
+            app.world().resource::<ChangedValues>().0,
+            [("DefaultValue", "ValueFromSystem")]
+        );
+
+        let events = app
+            .world()
+            .resource::<Events<PreferencesChanged<MyPreferences>>>();
+        let mut reader = events.get_cursor();
+        let sent: Vec<_> = reader
+            .read(events)
+            .map(|event| (event.old.value, event.new.value))
+            .collect();
+        assert_eq!(sent, [("DefaultValue", "ValueFromSystem")]);
+    }
+
     #[test]
     fn test_register_preferences_saves_back_to_reflect_map() {
         App::new()
@@ -308,4 +1230,46 @@ mod tests {
             )
             .run();
     }
+
+    #[derive(Resource, Reflect, Clone, PartialEq, Debug)]
+    struct Volume(f32);
+
+    #[test]
+    fn test_persist_resource_loads_and_stores_the_resource() {
+        let mut app = App::new();
+        app.insert_resource(Volume(1.0))
+            .persist_resource::<Volume>()
+            .init_resource::<ChangedValues>();
+
+        let mut reflect_map = {
+            let type_registry_arc = app.world().resource::<AppTypeRegistry>().0.clone();
+            PreferencesSerializableMap::empty(type_registry_arc)
+        };
+        reflect_map.set(Volume(0.5));
+        app.insert_resource(reflect_map);
+
+        app.update();
+        assert_eq!(app.world().resource::<Volume>(), &Volume(0.5));
+        assert!(
+            app.world()
+                .resource::<Events<PreferencesChanged<Volume>>>()
+                .is_empty()
+        );
+
+        app.world_mut().resource_mut::<Volume>().0 = 0.2;
+        app.update();
+
+        let map = app.world().resource::<PreferencesSerializableMap>();
+        assert_eq!(map.get::<Volume>(), Some(&Volume(0.2)));
+        assert!(
+            !app.world()
+                .resource::<Events<PreferencesChanged<Volume>>>()
+                .is_empty()
+        );
+
+        // The resource is accessible through the type registry, like any other preferences
+        app.world_mut()
+            .reset_preferences_by_type_id(std::any::TypeId::of::<Volume>());
+        assert_eq!(app.world().resource::<Volume>(), &Volume(1.0));
+    }
 }
@@ -0,0 +1,85 @@
+use crate::PreferencesType;
+use bevy::prelude::*;
+use std::collections::BTreeMap;
+
+/// Describes where the initial value of a preferences type came from.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum PreferencesLoadSource {
+    /// The value was loaded from storage as it was.
+    Loaded,
+    /// The value was loaded from storage, but it didn't match the current shape of the type,
+    /// so it was applied on top of the default value.
+    Migrated,
+    /// Nothing was stored for this type, so the default value was used.
+    DefaultedMissing,
+    /// The default value was used because there was an error loading the preferences.
+    DefaultedError,
+    /// A [`crate::PreferencesResource`] was already present before the preferences were assigned,
+    /// so it was kept as it was.
+    Overridden,
+}
+
+impl PreferencesLoadSource {
+    /// Returns true if the default value was used.
+    pub fn is_default(&self) -> bool {
+        matches!(
+            self,
+            PreferencesLoadSource::DefaultedMissing | PreferencesLoadSource::DefaultedError
+        )
+    }
+}
+
+/// Report of where every registered preferences type got its initial value from.
+/// It's built during [`crate::PreferencesSet::AssignResources`], so it's complete by [`Startup`].
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_simple_preferences::*;
+/// # #[derive(Reflect, Default)]
+/// # struct MyPreferences;
+/// App::new()
+///     .register_preferences::<MyPreferences>()
+///     .add_systems(Startup, |report: Res<PreferencesLoadReport>| {
+///         assert_eq!(
+///             report.get::<MyPreferences>(),
+///             Some(PreferencesLoadSource::DefaultedMissing)
+///         );
+///     })
+///     .run();
+/// ```
+#[derive(Resource, Default, Debug)]
+pub struct PreferencesLoadReport {
+    load_error: Option<String>,
+    sources: BTreeMap<&'static str, PreferencesLoadSource>,
+}
+
+impl PreferencesLoadReport {
+    /// Returns the source of the preferences of type `T`, if it has been assigned.
+    pub fn get<T: PreferencesType>(&self) -> Option<PreferencesLoadSource> {
+        self.get_by_type_path(T::type_path())
+    }
+
+    /// Returns the source of the preferences represented by the full `type_path`.
+    pub fn get_by_type_path(&self, type_path: &str) -> Option<PreferencesLoadSource> {
+        self.sources.get(type_path).copied()
+    }
+
+    /// Iterator over all the preferences type paths and their load source.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, PreferencesLoadSource)> + '_ {
+        self.sources
+            .iter()
+            .map(|(type_path, source)| (*type_path, *source))
+    }
+
+    /// Error that happened while loading the preferences from storage, if any.
+    pub fn load_error(&self) -> Option<&str> {
+        self.load_error.as_deref()
+    }
+
+    pub(crate) fn set_load_error(&mut self, error: impl ToString) {
+        self.load_error = Some(error.to_string());
+    }
+
+    pub(crate) fn record<T: PreferencesType>(&mut self, source: PreferencesLoadSource) {
+        self.sources.insert(T::type_path(), source);
+    }
+}
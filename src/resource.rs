@@ -1,4 +1,5 @@
-use crate::PreferencesType;
+use crate::defaults::{PreferencesDefaults, differs_from_default, reset_field};
+use crate::{PreferencesType, Result, clone_preferences};
 use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use std::ops::{Deref, DerefMut};
@@ -11,7 +12,10 @@ use std::ops::{Deref, DerefMut};
 pub struct PreferencesResource<T: PreferencesType>(T);
 
 impl<T: PreferencesType> PreferencesResource<T> {
-    pub(crate) fn new(value: T) -> Self {
+    /// Creates a new resource with the specified value.
+    /// If inserted before [`crate::PreferencesSet::AssignResources`], it will be used instead of
+    /// loading it from storage.
+    pub fn new(value: T) -> Self {
         Self(value)
     }
 }
@@ -33,6 +37,65 @@ impl<T: PreferencesType> PreferencesResource<T> {
 #[derive(SystemParam)]
 pub struct Preferences<'w, T: PreferencesType> {
     resource: ResMut<'w, PreferencesResource<T>>,
+    defaults: Res<'w, PreferencesDefaults>,
+}
+
+impl<T: PreferencesType> Preferences<'_, T> {
+    /// Returns the value the preferences had when they were registered.
+    pub fn default_value(&self) -> &T {
+        default_value(&self.defaults)
+    }
+
+    /// Returns true if the preferences are equal to their default value.
+    pub fn is_default(&self) -> bool {
+        is_default(&**self.resource, self.default_value())
+    }
+
+    /// Returns true if the field in `path` is different from its default value.
+    /// Uses the same syntax as [`bevy::reflect::GetPath`], e.g: `window.present_mode`.
+    pub fn differs_from_default(&self, path: &str) -> bool {
+        differs_from_default(&**self.resource, self.default_value(), path)
+    }
+
+    /// Resets the preferences back to their default value.
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_simple_preferences::*;
+    /// # #[derive(Reflect, Default)]
+    /// # struct AudioPreferences {
+    /// #    volume: f32,
+    /// # }
+    /// fn reset_audio(mut audio: Preferences<AudioPreferences>) {
+    ///     audio.reset();
+    ///     assert!(audio.is_default());
+    /// }
+    /// ```
+    pub fn reset(&mut self) {
+        **self.resource = clone_preferences(default_value(&self.defaults));
+    }
+
+    /// Resets the field in `path` back to its default value.
+    /// Returns [`crate::PreferencesError::InvalidFieldPath`] if the path doesn't exist.
+    pub fn reset_field(&mut self, path: &str) -> Result<()> {
+        reset_field(
+            &mut **self.resource,
+            default_value::<T>(&self.defaults),
+            path,
+        )
+    }
+}
+
+fn default_value<T: PreferencesType>(defaults: &PreferencesDefaults) -> &T {
+    defaults.get::<T>().unwrap_or_else(|| {
+        panic!(
+            "Default value of preferences {} not found",
+            T::short_type_path()
+        )
+    })
+}
+
+fn is_default<T: PreferencesType>(value: &T, default_value: &T) -> bool {
+    value.reflect_partial_eq(default_value).unwrap_or(false)
 }
 
 impl<T> Deref for Preferences<'_, T>
@@ -63,3 +126,65 @@ where
         (**self.resource).eq(other)
     }
 }
+
+/// Read-only system param that allows to read preferences of a type `T`.
+/// Unlike [`Preferences`], it doesn't require exclusive access to the preferences,
+/// so systems that only read them can run in parallel.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_simple_preferences::*;
+/// # #[derive(Reflect, Default)]
+/// # struct MyPreferences {
+/// #    volume: f32,
+/// # }
+/// let app = App::new()
+///     .register_preferences::<MyPreferences>()
+///     .add_systems(Update, |preferences: PreferencesRef<MyPreferences>| {
+///         assert_eq!(preferences.volume, 0.0);
+///     })
+///     .run();
+///
+/// ```
+#[derive(SystemParam)]
+pub struct PreferencesRef<'w, T: PreferencesType> {
+    resource: Res<'w, PreferencesResource<T>>,
+    defaults: Res<'w, PreferencesDefaults>,
+}
+
+impl<T: PreferencesType> PreferencesRef<'_, T> {
+    /// Returns the value the preferences had when they were registered.
+    pub fn default_value(&self) -> &T {
+        default_value(&self.defaults)
+    }
+
+    /// Returns true if the preferences are equal to their default value.
+    pub fn is_default(&self) -> bool {
+        is_default(&**self.resource, self.default_value())
+    }
+
+    /// Returns true if the field in `path` is different from its default value.
+    /// Uses the same syntax as [`bevy::reflect::GetPath`], e.g: `window.present_mode`.
+    pub fn differs_from_default(&self, path: &str) -> bool {
+        differs_from_default(&**self.resource, self.default_value(), path)
+    }
+}
+
+impl<T> Deref for PreferencesRef<'_, T>
+where
+    T: PreferencesType,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.resource
+    }
+}
+
+impl<T> PartialEq<T> for PreferencesRef<'_, T>
+where
+    T: PreferencesType + PartialEq,
+{
+    fn eq(&self, other: &T) -> bool {
+        (**self.resource).eq(other)
+    }
+}
@@ -0,0 +1,373 @@
+use crate::changes::field_path;
+use crate::validation::{AllowedVariants, PreferencesRange};
+use crate::{ReflectInternalPreferences, ReflectPreferences};
+use bevy::prelude::*;
+use bevy::reflect::attributes::CustomAttributes;
+use bevy::reflect::{TypeInfo, TypeRegistry};
+use std::any::TypeId;
+
+/// Field attribute with the name shown to the user.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_simple_preferences::*;
+/// #[derive(Reflect, Default)]
+/// #[reflect(@DisplayName("Audio"), @Category("Sound"))]
+/// struct AudioPreferences {
+///     #[reflect(@DisplayName("Master volume"), @Description("Volume of every sound"))]
+///     #[reflect(@PreferencesRange::new(0.0, 100.0), @Step(5.0), @Unit("%"))]
+///     master_volume: f32,
+///     #[reflect(@WidgetHint::Toggle)]
+///     muted: bool,
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy, Debug, PartialEq)]
+pub struct DisplayName(pub &'static str);
+
+/// Field attribute with a longer description, e.g. for tooltips.
+#[derive(Reflect, Clone, Copy, Debug, PartialEq)]
+pub struct Description(pub &'static str);
+
+/// Field attribute with the category used to group fields in a menu.
+#[derive(Reflect, Clone, Copy, Debug, PartialEq)]
+pub struct Category(pub &'static str);
+
+/// Field attribute with the increment of a numeric field.
+#[derive(Reflect, Clone, Copy, Debug, PartialEq)]
+pub struct Step(pub f64);
+
+/// Field attribute with the unit of a numeric field, e.g. `%` or `px`.
+#[derive(Reflect, Clone, Copy, Debug, PartialEq)]
+pub struct Unit(pub &'static str);
+
+/// Field attribute with the widget that should be used to edit a field.
+#[derive(Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WidgetHint {
+    /// A slider, for numeric fields with a range.
+    Slider,
+    /// A dropdown, for enum fields.
+    Dropdown,
+    /// A toggle, for boolean fields.
+    Toggle,
+    /// A text input.
+    Text,
+}
+
+/// Metadata of a preferences type or field, taken from its attributes.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PreferencesMetadata {
+    /// See [`DisplayName`].
+    pub display_name: Option<&'static str>,
+    /// See [`Description`].
+    pub description: Option<&'static str>,
+    /// See [`Category`].
+    pub category: Option<&'static str>,
+    /// See [`PreferencesRange`].
+    pub range: Option<PreferencesRange>,
+    /// See [`Step`].
+    pub step: Option<f64>,
+    /// See [`Unit`].
+    pub unit: Option<&'static str>,
+    /// See [`WidgetHint`]. If not specified, it's inferred from the field type.
+    pub widget: Option<WidgetHint>,
+}
+
+impl PreferencesMetadata {
+    fn from_custom_attributes(custom_attributes: &CustomAttributes) -> Self {
+        Self {
+            display_name: custom_attributes.get::<DisplayName>().map(|name| name.0),
+            description: custom_attributes
+                .get::<Description>()
+                .map(|description| description.0),
+            category: custom_attributes
+                .get::<Category>()
+                .map(|category| category.0),
+            range: custom_attributes.get::<PreferencesRange>().copied(),
+            step: custom_attributes.get::<Step>().map(|step| step.0),
+            unit: custom_attributes.get::<Unit>().map(|unit| unit.0),
+            widget: custom_attributes.get::<WidgetHint>().copied(),
+        }
+    }
+}
+
+/// Schema of a single field of a preferences type.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PreferencesFieldSchema {
+    /// Name of the field, or its index for tuple structs.
+    pub name: String,
+    /// Path of the field from the root of the preferences, as in [`bevy::reflect::GetPath`].
+    pub path: String,
+    /// Type path of the field.
+    pub type_path: &'static str,
+    /// Metadata of the field.
+    pub metadata: PreferencesMetadata,
+    /// Allowed variants, if the field is an enum. See [`AllowedVariants`].
+    pub variants: Vec<&'static str>,
+    /// Fields of the field, if it's a struct.
+    pub fields: Vec<PreferencesFieldSchema>,
+}
+
+/// Schema of a registered preferences type.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PreferencesTypeSchema {
+    /// Type id of the preferences.
+    pub type_id: TypeId,
+    /// Type path of the preferences.
+    pub type_path: &'static str,
+    /// Short type path of the preferences.
+    pub short_type_path: &'static str,
+    /// Metadata of the preferences type itself.
+    pub metadata: PreferencesMetadata,
+    /// Fields of the preferences.
+    pub fields: Vec<PreferencesFieldSchema>,
+}
+
+/// Structured description of every registered preferences type and their fields,
+/// including the metadata attached as attributes, so settings menus can be built generically.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_simple_preferences::*;
+/// # #[derive(Reflect, Default)]
+/// # struct AudioPreferences {
+/// #     #[reflect(@DisplayName("Master volume"))]
+/// #     master_volume: f32,
+/// # }
+/// fn build_settings_menu(type_registry: Res<AppTypeRegistry>) {
+///     let schema = PreferencesSchema::new(&type_registry.read());
+///     for preferences in schema.iter() {
+///         for field in &preferences.fields {
+///             let label = field.metadata.display_name.unwrap_or(&field.name);
+///             // ...
+///         }
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PreferencesSchema {
+    types: Vec<PreferencesTypeSchema>,
+}
+
+impl PreferencesSchema {
+    /// Creates the schema of every type registered with [`ReflectPreferences`], sorted by type path.
+    pub fn new(type_registry: &TypeRegistry) -> Self {
+        let mut types: Vec<_> = type_registry
+            .iter_with_data::<ReflectPreferences>()
+            .filter(|(type_registration, _)| {
+                type_registration
+                    .data::<ReflectInternalPreferences>()
+                    .is_none()
+            })
+            .map(|(type_registration, _)| type_schema(type_registration.type_info()))
+            .collect();
+        types.sort_by_key(|type_schema| type_schema.type_path);
+        Self { types }
+    }
+
+    /// Returns the schema of the preferences with the specified type path or short type path.
+    pub fn get(&self, type_path: &str) -> Option<&PreferencesTypeSchema> {
+        self.types.iter().find(|type_schema| {
+            type_schema.type_path == type_path || type_schema.short_type_path == type_path
+        })
+    }
+
+    /// Iterator over the schema of every registered preferences type.
+    pub fn iter(&self) -> impl Iterator<Item = &PreferencesTypeSchema> {
+        self.types.iter()
+    }
+}
+
+fn type_schema(type_info: &'static TypeInfo) -> PreferencesTypeSchema {
+    let metadata = match type_info {
+        TypeInfo::Struct(info) => {
+            PreferencesMetadata::from_custom_attributes(info.custom_attributes())
+        }
+        TypeInfo::TupleStruct(info) => {
+            PreferencesMetadata::from_custom_attributes(info.custom_attributes())
+        }
+        TypeInfo::Enum(info) => {
+            PreferencesMetadata::from_custom_attributes(info.custom_attributes())
+        }
+        _ => PreferencesMetadata::default(),
+    };
+
+    PreferencesTypeSchema {
+        type_id: type_info.type_id(),
+        type_path: type_info.type_path(),
+        short_type_path: type_info.type_path_table().short_path(),
+        metadata,
+        fields: fields_schema(type_info, ""),
+    }
+}
+
+fn fields_schema(type_info: &'static TypeInfo, path: &str) -> Vec<PreferencesFieldSchema> {
+    match type_info {
+        TypeInfo::Struct(info) => info
+            .iter()
+            .map(|field| {
+                field_schema(
+                    field.name().to_string(),
+                    field_path(path, field.name()),
+                    field.type_path(),
+                    field.type_id(),
+                    field.type_info(),
+                    field.custom_attributes(),
+                )
+            })
+            .collect(),
+        TypeInfo::TupleStruct(info) => info
+            .iter()
+            .map(|field| {
+                field_schema(
+                    field.index().to_string(),
+                    field_path(path, field.index()),
+                    field.type_path(),
+                    field.type_id(),
+                    field.type_info(),
+                    field.custom_attributes(),
+                )
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn field_schema(
+    name: String,
+    path: String,
+    type_path: &'static str,
+    type_id: TypeId,
+    type_info: Option<&'static TypeInfo>,
+    custom_attributes: &CustomAttributes,
+) -> PreferencesFieldSchema {
+    let mut metadata = PreferencesMetadata::from_custom_attributes(custom_attributes);
+
+    let variants = match type_info {
+        Some(TypeInfo::Enum(info)) => {
+            let allowed_variants = custom_attributes.get::<AllowedVariants>();
+            info.variant_names()
+                .iter()
+                .copied()
+                .filter(|variant| {
+                    allowed_variants.is_none_or(|allowed| allowed.0.iter().any(|v| v == variant))
+                })
+                .collect()
+        }
+        _ => Vec::new(),
+    };
+
+    metadata.widget = metadata.widget.or_else(|| {
+        if type_id == TypeId::of::<bool>() {
+            Some(WidgetHint::Toggle)
+        } else if type_id == TypeId::of::<String>() {
+            Some(WidgetHint::Text)
+        } else if !variants.is_empty() {
+            Some(WidgetHint::Dropdown)
+        } else if metadata.range.is_some() {
+            Some(WidgetHint::Slider)
+        } else {
+            None
+        }
+    });
+
+    let fields = type_info
+        .map(|type_info| fields_schema(type_info, &path))
+        .unwrap_or_default();
+
+    PreferencesFieldSchema {
+        name,
+        path,
+        type_path,
+        metadata,
+        variants,
+        fields,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        AllowedVariants, Category, Description, DisplayName, PreferencesRange, PreferencesSchema,
+        RegisterPreferencesExt, Step, Unit, WidgetHint,
+    };
+    use bevy::prelude::*;
+
+    #[derive(Reflect, Default)]
+    enum Quality {
+        Low,
+        #[default]
+        Medium,
+        Experimental,
+    }
+
+    #[derive(Reflect, Default)]
+    struct Window {
+        #[reflect(@DisplayName("Fullscreen"))]
+        fullscreen: bool,
+    }
+
+    #[derive(Reflect, Default)]
+    #[reflect(@DisplayName("Graphics"), @Category("Video"))]
+    struct GraphicsPreferences {
+        #[reflect(@DisplayName("Brightness"), @Description("Brightness of the screen"))]
+        #[reflect(@PreferencesRange::new(0.0, 100.0), @Step(5.0), @Unit("%"))]
+        brightness: f32,
+        #[reflect(@AllowedVariants::new(["Low", "Medium"]))]
+        quality: Quality,
+        #[reflect(@WidgetHint::Text)]
+        fps_limit: u32,
+        window: Window,
+    }
+
+    #[test]
+    fn test_schema_contains_field_metadata() {
+        let mut app = App::new();
+        app.register_preferences::<GraphicsPreferences>();
+
+        let schema = PreferencesSchema::new(&app.world().resource::<AppTypeRegistry>().read());
+        let graphics = schema
+            .get("GraphicsPreferences")
+            .expect("GraphicsPreferences schema");
+
+        assert_eq!(graphics.metadata.display_name, Some("Graphics"));
+        assert_eq!(graphics.metadata.category, Some("Video"));
+
+        let brightness = &graphics.fields[0];
+        assert_eq!(brightness.path, "brightness");
+        assert_eq!(brightness.metadata.display_name, Some("Brightness"));
+        assert_eq!(
+            brightness.metadata.description,
+            Some("Brightness of the screen")
+        );
+        assert_eq!(
+            brightness.metadata.range,
+            Some(PreferencesRange::new(0.0, 100.0))
+        );
+        assert_eq!(brightness.metadata.step, Some(5.0));
+        assert_eq!(brightness.metadata.unit, Some("%"));
+        assert_eq!(brightness.metadata.widget, Some(WidgetHint::Slider));
+
+        let quality = &graphics.fields[1];
+        assert_eq!(quality.variants, vec!["Low", "Medium"]);
+        assert_eq!(quality.metadata.widget, Some(WidgetHint::Dropdown));
+
+        assert_eq!(graphics.fields[2].metadata.widget, Some(WidgetHint::Text));
+
+        let fullscreen = &graphics.fields[3].fields[0];
+        assert_eq!(fullscreen.path, "window.fullscreen");
+        assert_eq!(fullscreen.metadata.display_name, Some("Fullscreen"));
+        assert_eq!(fullscreen.metadata.widget, Some(WidgetHint::Toggle));
+    }
+
+    #[test]
+    fn test_schema_only_contains_preferences() {
+        let mut app = App::new();
+        app.register_type::<Window>()
+            .register_preferences::<GraphicsPreferences>();
+
+        let schema = PreferencesSchema::new(&app.world().resource::<AppTypeRegistry>().read());
+        let type_paths: Vec<_> = schema
+            .iter()
+            .map(|preferences| preferences.short_type_path)
+            .collect();
+        assert_eq!(type_paths, vec!["GraphicsPreferences"]);
+    }
+}
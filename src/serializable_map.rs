@@ -1,10 +1,12 @@
 //! Contains [`PreferencesSerializableMap`] that allows preferences to be serialize and deserialize using reflection.
 //!
 use crate::registry::PreferencesRegistryData;
-use crate::{PreferencesError, PreferencesType};
+use crate::report::PreferencesLoadSource;
+use crate::sparse::{SparseSerializer, apply_sparse, is_equal};
+use crate::{PreferencesError, PreferencesType, ReflectInternalPreferences, clone_preferences};
 use bevy::prelude::*;
 use bevy::reflect::serde::{TypedReflectDeserializer, TypedReflectSerializer};
-use bevy::reflect::{TypeInfo, TypeRegistry, TypeRegistryArc};
+use bevy::reflect::{ReflectRef, TypeInfo, TypeRegistry, TypeRegistryArc};
 use serde::de::{DeserializeSeed, MapAccess, Visitor};
 use serde::ser::SerializeMap;
 use serde::{Deserializer, Serialize, Serializer};
@@ -92,13 +94,31 @@ use std::fmt::{Debug, Formatter};
 /// assert_eq!(&contents, "[MyPluginPreferences]\ndo_things = true\n");
 /// ```
 ///
+/// ### Sparse storage
+///
+/// When [`crate::PreferencesPlugin::with_sparse_storage`] is used, only the fields that differ
+/// from the registered default values are serialized. Loaded values are kept as they are until
+/// their preferences are registered, and then they are applied over the default value.
+/// This way, users that never modified a field get the new default when it changes.
+///
 
 #[derive(Resource, TypePath)]
 pub struct PreferencesSerializableMap {
     values: BTreeMap<String, Box<dyn Reflect>>,
+    load_sources: BTreeMap<String, PreferencesLoadSource>,
+    sparse: Option<SparseValues>,
     type_registry_arc: TypeRegistryArc,
 }
 
+/// Values used only when the storage is sparse.
+#[derive(Default)]
+struct SparseValues {
+    /// Default values, only fields that differ from them are serialized.
+    defaults: BTreeMap<String, Box<dyn Reflect>>,
+    /// Loaded values that have not been applied over their default values yet.
+    pending: BTreeMap<String, Box<dyn PartialReflect>>,
+}
+
 impl Debug for PreferencesSerializableMap {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let mut debug = f.debug_map();
@@ -136,12 +156,19 @@ impl FromWorld for PreferencesSerializableMap {
     }
 }
 
+// Preferences managed by this crate are stored under their full type path, so they never collide with user types
 fn effective_type_path<'a>(
     type_path: &'a str,
     short_type_path: &'a str,
     type_registry: &TypeRegistry,
 ) -> &'a str {
-    if let Some(type_registration) = type_registry.get_with_short_type_path(short_type_path) {
+    let is_internal = type_registry
+        .get_with_type_path(type_path)
+        .is_some_and(|registration| registration.contains::<ReflectInternalPreferences>());
+    if is_internal {
+        type_path
+    } else if let Some(type_registration) = type_registry.get_with_short_type_path(short_type_path)
+    {
         let registered_type_path = type_registration.type_info().type_path();
         assert_eq!(
             registered_type_path, type_path,
@@ -162,10 +189,41 @@ impl PreferencesSerializableMap {
     pub fn empty(type_registry_arc: TypeRegistryArc) -> Self {
         Self {
             values: BTreeMap::new(),
+            load_sources: BTreeMap::new(),
+            sparse: None,
             type_registry_arc,
         }
     }
 
+    /// Creates a new empty storage map that only serializes values that differ from the defaults.
+    pub fn empty_sparse(type_registry_arc: TypeRegistryArc) -> Self {
+        Self {
+            sparse: Some(SparseValues::default()),
+            ..Self::empty(type_registry_arc)
+        }
+    }
+
+    /// Creates a sparse storage map using the specified dynamic values, which may only contain
+    /// the fields that differ from the defaults.
+    /// Values are kept until their preferences are registered, and then applied over their default value.
+    pub fn from_sparse_dynamic_values(
+        values: impl IntoIterator<Item = (String, Box<dyn PartialReflect>)>,
+        type_registry_arc: TypeRegistryArc,
+    ) -> Self {
+        Self {
+            sparse: Some(SparseValues {
+                defaults: BTreeMap::new(),
+                pending: values.into_iter().collect(),
+            }),
+            ..Self::empty(type_registry_arc)
+        }
+    }
+
+    /// Returns true if only values that differ from the defaults are serialized.
+    pub fn is_sparse(&self) -> bool {
+        self.sparse.is_some()
+    }
+
     /// Creates a storage map using the specified dynamic values.
     /// Values are converted into concrete types using the `FromReflect` implementation.
     pub fn from_dynamic_values(
@@ -173,6 +231,7 @@ impl PreferencesSerializableMap {
         type_registry_arc: TypeRegistryArc,
     ) -> Self {
         let values = values.into_iter();
+        let mut load_sources = BTreeMap::new();
 
         // This is scope is to make the borrow checker happy
         let values = {
@@ -184,10 +243,17 @@ impl PreferencesSerializableMap {
                         let registry_data =
                             PreferencesRegistryData::from_type_info(&type_registry, type_info);
 
-                        let new_value = registry_data.convert_to_concrete_type(value);
+                        let Some((new_value, source)) =
+                            registry_data.try_convert_to_concrete_type(value)
+                        else {
+                            error!("Preferences {key} could not be converted, ignoring them");
+                            load_sources.insert(key, PreferencesLoadSource::DefaultedError);
+                            return None;
+                        };
 
                         debug_assert!(!new_value.is_dynamic(), "Dynamic value generated");
 
+                        load_sources.insert(key.clone(), source);
                         Some((key, new_value))
                     } else {
                         // TODO: Should we panic instead?, or at least a warning
@@ -199,6 +265,8 @@ impl PreferencesSerializableMap {
 
         Self {
             values,
+            load_sources,
+            sparse: None,
             type_registry_arc,
         }
     }
@@ -302,6 +370,53 @@ impl PreferencesSerializableMap {
             .map(|val| *val)
     }
 
+    /// Returns how the entry of type `T` was loaded, if it was loaded from storage.
+    pub(crate) fn load_source<T: PreferencesType>(&self) -> Option<PreferencesLoadSource> {
+        let type_path = self.effective_type_path_from_type::<T>();
+        self.load_sources.get(type_path).copied()
+    }
+
+    /// Sets the default value of `T`, and applies any pending sparse value over it.
+    /// Does nothing if the map is not sparse.
+    pub(crate) fn resolve_sparse<T: PreferencesType>(&mut self, default_value: &T) {
+        let type_path = self.effective_type_path_from_type::<T>();
+        let Some(sparse) = &mut self.sparse else {
+            return;
+        };
+
+        sparse.defaults.insert(
+            type_path.to_owned(),
+            Box::new(clone_preferences(default_value)),
+        );
+
+        let Some(sparse_value) = sparse.pending.remove(type_path) else {
+            return;
+        };
+
+        let value = match sparse_value.reflect_ref() {
+            ReflectRef::Struct(sparse_struct) => match default_value.reflect_ref() {
+                ReflectRef::Struct(default_struct) => {
+                    T::from_reflect(&apply_sparse(default_struct, sparse_struct))
+                }
+                _ => T::from_reflect(&*sparse_value),
+            },
+            _ => T::from_reflect(&*sparse_value),
+        };
+
+        match value {
+            Some(value) => {
+                self.load_sources
+                    .insert(type_path.to_owned(), PreferencesLoadSource::Loaded);
+                self.values.insert(type_path.to_owned(), Box::new(value));
+            }
+            None => {
+                error!("Preferences {type_path} could not be converted, ignoring them");
+                self.load_sources
+                    .insert(type_path.to_owned(), PreferencesLoadSource::DefaultedError);
+            }
+        }
+    }
+
     /// Returns if the map is empty
     pub fn is_empty(&self) -> bool {
         self.values.is_empty()
@@ -318,6 +433,10 @@ impl Serialize for PreferencesSerializableMap {
     where
         S: Serializer,
     {
+        if let Some(sparse) = &self.sparse {
+            return self.serialize_sparse(sparse, serializer);
+        }
+
         let type_registry = self.type_registry_arc.read();
         let values = &self.values;
 
@@ -333,6 +452,48 @@ impl Serialize for PreferencesSerializableMap {
     }
 }
 
+impl PreferencesSerializableMap {
+    fn serialize_sparse<S>(&self, sparse: &SparseValues, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let type_registry = self.type_registry_arc.read();
+
+        // Values equal to their defaults are not stored at all
+        let values = self.values.iter().filter_map(|(type_path, value)| {
+            let default_value = sparse
+                .defaults
+                .get(type_path)
+                .map(|default_value| default_value.as_partial_reflect());
+            if default_value
+                .is_some_and(|default_value| is_equal(value.as_partial_reflect(), default_value))
+            {
+                return None;
+            }
+            Some((
+                type_path,
+                SparseSerializer::new(value.as_partial_reflect(), default_value, &type_registry),
+            ))
+        });
+
+        // Pending values are stored back as they were loaded
+        let pending = sparse.pending.iter().map(|(type_path, value)| {
+            (
+                type_path,
+                SparseSerializer::new(&**value, None, &type_registry),
+            )
+        });
+
+        let entries: BTreeMap<_, _> = values.chain(pending).collect();
+
+        let mut map_serializer = serializer.serialize_map(Some(entries.len()))?;
+        for (type_path, value) in entries {
+            map_serializer.serialize_entry(type_path, &value)?;
+        }
+        map_serializer.end()
+    }
+}
+
 /// [`DeserializeSeed`] used to deserialize a [`PreferencesSerializableMap`].
 /// Is required to deserialize this way in order to have a reference to
 /// the [`TypeRegistry`].
@@ -340,18 +501,30 @@ impl Serialize for PreferencesSerializableMap {
 /// Best way to get a new seed is to call [`PreferencesSerializableMap::deserialize_seed`]
 pub struct PreferencesSerializableMapSeed {
     type_registry_arc: TypeRegistryArc,
+    sparse: bool,
 }
 
 impl PreferencesSerializableMapSeed {
-    pub(crate) fn new(type_registry_arc: TypeRegistryArc) -> Self {
-        Self { type_registry_arc }
+    pub(crate) fn new(type_registry_arc: TypeRegistryArc, sparse: bool) -> Self {
+        Self {
+            type_registry_arc,
+            sparse,
+        }
     }
 }
 
 impl PreferencesSerializableMap {
     /// Creates an [`PreferencesSerializableMapSeed`] that allows deserialization of [`PreferencesSerializableMap`].
     pub fn deserialize_seed(type_registry_arc: TypeRegistryArc) -> PreferencesSerializableMapSeed {
-        PreferencesSerializableMapSeed::new(type_registry_arc)
+        PreferencesSerializableMapSeed::new(type_registry_arc, false)
+    }
+
+    /// Creates an [`PreferencesSerializableMapSeed`] that allows deserialization of a sparse
+    /// [`PreferencesSerializableMap`]. See [`Self::from_sparse_dynamic_values`].
+    pub fn deserialize_sparse_seed(
+        type_registry_arc: TypeRegistryArc,
+    ) -> PreferencesSerializableMapSeed {
+        PreferencesSerializableMapSeed::new(type_registry_arc, true)
     }
 }
 
@@ -408,6 +581,13 @@ impl<'de> DeserializeSeed<'de> for PreferencesSerializableMapSeed {
             type_registry_arc: type_registry_arc.clone(),
         })?;
 
+        if self.sparse {
+            return Ok(PreferencesSerializableMap::from_sparse_dynamic_values(
+                values,
+                type_registry_arc,
+            ));
+        }
+
         Ok(PreferencesSerializableMap::from_dynamic_values(
             values,
             type_registry_arc,
@@ -650,6 +830,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_sparse_only_serializes_changed_fields() {
+        let mut map = PreferencesSerializableMap::from_sparse_dynamic_values(
+            [("Bar".to_string(), Bar("Pending".into()).clone_value())],
+            get_registry(),
+        );
+
+        map.resolve_sparse(&Foo::default());
+        assert_eq!(toml::to_string(&map).unwrap(), "Bar = \"Pending\"\n");
+
+        map.set(Foo {
+            field: 3,
+            option: None,
+        });
+        assert_eq!(
+            toml::to_string(&map).unwrap(),
+            "Bar = \"Pending\"\n\n[Foo]\nfield = 3\n"
+        );
+
+        map.resolve_sparse(&Bar::default());
+        assert_eq!(map.get::<Bar>(), Some(&Bar("Pending".into())));
+    }
+
     #[track_caller]
     pub fn assert_de_seed_tokens<'de, T>(
         value: &<T as serde::de::DeserializeSeed<'de>>::Value,
@@ -678,9 +881,8 @@ mod tests {
             option: None,
         });
 
-        let deserializer = PreferencesSerializableMapSeed {
-            type_registry_arc: map.type_registry_arc.clone(),
-        };
+        let deserializer =
+            PreferencesSerializableMapSeed::new(map.type_registry_arc.clone(), false);
 
         // It takes the default value for Bar
         assert_de_seed_tokens(
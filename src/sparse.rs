@@ -0,0 +1,139 @@
+use bevy::reflect::serde::{SerializationData, TypedReflectSerializer};
+use bevy::reflect::{
+    DynamicStruct, PartialReflect, ReflectRef, ReflectSerialize, Struct, TypeInfo, TypeRegistry,
+};
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+
+/// Serializes only the fields of `value` that are different from `default_value`.
+///
+/// Structs are compared field by field recursively, any other value is serialized as a whole.
+/// Types with a custom [`serde::Serialize`] implementation are always serialized as a whole.
+pub(crate) struct SparseSerializer<'a> {
+    value: &'a dyn PartialReflect,
+    default_value: Option<&'a dyn PartialReflect>,
+    type_registry: &'a TypeRegistry,
+}
+
+impl<'a> SparseSerializer<'a> {
+    pub fn new(
+        value: &'a dyn PartialReflect,
+        default_value: Option<&'a dyn PartialReflect>,
+        type_registry: &'a TypeRegistry,
+    ) -> Self {
+        Self {
+            value,
+            default_value,
+            type_registry,
+        }
+    }
+
+    fn is_field_skipped(&self, name: &str) -> bool {
+        let Some(TypeInfo::Struct(struct_info)) = self.value.get_represented_type_info() else {
+            return false;
+        };
+        let (Some(index), Some(serialization_data)) = (
+            struct_info.index_of(name),
+            self.type_registry
+                .get_type_data::<SerializationData>(struct_info.type_id()),
+        ) else {
+            return false;
+        };
+        serialization_data.is_field_skipped(index)
+    }
+
+    fn has_custom_serialize(&self) -> bool {
+        self.value
+            .get_represented_type_info()
+            .and_then(|type_info| {
+                self.type_registry
+                    .get_type_data::<ReflectSerialize>(type_info.type_id())
+            })
+            .is_some()
+    }
+
+    fn serialize_struct<S: Serializer>(
+        &self,
+        value: &dyn Struct,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let default_value = self
+            .default_value
+            .and_then(|default_value| default_value.reflect_ref().as_struct().ok());
+
+        let fields: Vec<_> = value
+            .iter_fields()
+            .enumerate()
+            .filter_map(|(index, field)| {
+                let name = value.name_at(index)?;
+                if self.is_field_skipped(name) {
+                    return None;
+                }
+                let default_field =
+                    default_value.and_then(|default_value| default_value.field(name));
+                if default_field.is_some_and(|default_field| is_equal(field, default_field)) {
+                    return None;
+                }
+                Some((
+                    name,
+                    SparseSerializer::new(field, default_field, self.type_registry),
+                ))
+            })
+            .collect();
+
+        let mut map_serializer = serializer.serialize_map(Some(fields.len()))?;
+        for (name, field) in fields {
+            map_serializer.serialize_entry(name, &field)?;
+        }
+        map_serializer.end()
+    }
+}
+
+impl Serialize for SparseSerializer<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.value.reflect_ref() {
+            ReflectRef::Struct(value) if !self.has_custom_serialize() => {
+                self.serialize_struct(value, serializer)
+            }
+            _ => TypedReflectSerializer::new(self.value, self.type_registry).serialize(serializer),
+        }
+    }
+}
+
+pub(crate) fn is_equal(value: &dyn PartialReflect, other: &dyn PartialReflect) -> bool {
+    value.reflect_partial_eq(other).unwrap_or(false)
+}
+
+/// Applies a sparse value over its default value, returning the dynamic representation of the result.
+///
+/// Structs are applied field by field recursively, any other value replaces the default one.
+pub(crate) fn apply_sparse(default_value: &dyn Struct, sparse: &dyn Struct) -> DynamicStruct {
+    let mut value = DynamicStruct::default();
+    value.set_represented_type(default_value.get_represented_type_info());
+
+    for (index, default_field) in default_value.iter_fields().enumerate() {
+        let name = default_value
+            .name_at(index)
+            .expect("Field index out of bounds");
+
+        let field = match (default_field.reflect_ref(), sparse.field(name)) {
+            (_, None) => default_field.clone_value(),
+            (ReflectRef::Struct(default_field), Some(sparse_field)) => {
+                match sparse_field.reflect_ref() {
+                    ReflectRef::Struct(sparse_field) => {
+                        Box::new(apply_sparse(default_field, sparse_field))
+                    }
+                    _ => sparse_field.clone_value(),
+                }
+            }
+            (_, Some(sparse_field)) => sparse_field.clone_value(),
+        };
+
+        value.insert_boxed(name, field);
+    }
+
+    value
+}
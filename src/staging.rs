@@ -0,0 +1,374 @@
+use crate::resource::PreferencesResource;
+use crate::{PreferencesType, clone_preferences};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use std::time::Duration;
+
+struct PendingConfirmation<T> {
+    previous: T,
+    remaining: Duration,
+}
+
+/// Staged edits of the preferences of type `T`.
+/// Use [`StagedPreferences`] to access it.
+#[derive(Resource)]
+pub(crate) struct PreferencesStaging<T: PreferencesType> {
+    draft: Option<T>,
+    confirmation: Option<PendingConfirmation<T>>,
+}
+
+impl<T: PreferencesType> PreferencesStaging<T> {
+    /// Value to keep stored while the applied preferences are pending confirmation.
+    pub(crate) fn confirmed_value(&self) -> Option<&T> {
+        self.confirmation.as_ref().map(|pending| &pending.previous)
+    }
+}
+
+impl<T: PreferencesType> Default for PreferencesStaging<T> {
+    fn default() -> Self {
+        Self {
+            draft: None,
+            confirmation: None,
+        }
+    }
+}
+
+/// Event sent when applied preferences are reverted because they were not confirmed in time.
+/// See [`StagedPreferences::apply_with_confirmation`].
+#[derive(Event)]
+pub struct PreferencesReverted<T: PreferencesType> {
+    /// Value of the preferences that was not confirmed.
+    pub unconfirmed: T,
+}
+
+/// System param that allows to edit a draft of the preferences of type `T`,
+/// that is only written into the preferences once applied.
+///
+/// Drafts are never stored, so discarding them has no effect on the preferences.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_simple_preferences::*;
+/// # use std::time::Duration;
+/// # #[derive(Reflect, Default)]
+/// # struct DisplayPreferences {
+/// #    fullscreen: bool,
+/// # }
+/// fn toggle_fullscreen(
+///     keys: Res<ButtonInput<KeyCode>>,
+///     mut display: StagedPreferences<DisplayPreferences>,
+/// ) {
+///     if keys.just_pressed(KeyCode::F11) {
+///         display.begin_edit();
+///         let draft = display.draft_mut().unwrap();
+///         draft.fullscreen = !draft.fullscreen;
+///
+///         // Reverts back to the previous value unless `confirm` is called in the next 15 seconds.
+///         display.apply_with_confirmation(Duration::from_secs(15));
+///     }
+///
+///     if keys.just_pressed(KeyCode::Enter) {
+///         display.confirm();
+///     }
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct StagedPreferences<'w, T: PreferencesType> {
+    preferences: ResMut<'w, PreferencesResource<T>>,
+    staging: ResMut<'w, PreferencesStaging<T>>,
+}
+
+impl<T: PreferencesType> StagedPreferences<'_, T> {
+    /// Current value of the preferences, without the draft changes.
+    pub fn current(&self) -> &T {
+        &self.preferences
+    }
+
+    /// Starts editing a draft, which is a copy of the current preferences.
+    /// Any previous draft is discarded.
+    pub fn begin_edit(&mut self) {
+        self.staging.draft = Some(clone_preferences(&**self.preferences));
+    }
+
+    /// Returns true if there is a draft being edited.
+    pub fn is_editing(&self) -> bool {
+        self.staging.draft.is_some()
+    }
+
+    /// Returns the draft, if any is being edited.
+    pub fn draft(&self) -> Option<&T> {
+        self.staging.draft.as_ref()
+    }
+
+    /// Returns the draft mutably, if any is being edited.
+    pub fn draft_mut(&mut self) -> Option<&mut T> {
+        self.staging.draft.as_mut()
+    }
+
+    /// Returns true if the draft is different from the current preferences.
+    pub fn is_draft_changed(&self) -> bool {
+        self.staging.draft.as_ref().is_some_and(|draft| {
+            !draft
+                .reflect_partial_eq(&**self.preferences)
+                .unwrap_or(false)
+        })
+    }
+
+    /// Discards the draft, without modifying the preferences.
+    pub fn discard(&mut self) {
+        self.staging.draft = None;
+    }
+
+    /// Writes the draft into the preferences, finishing the edit.
+    /// Any pending confirmation is confirmed.
+    /// Returns false if there was no draft being edited.
+    pub fn apply(&mut self) -> bool {
+        let Some(draft) = self.staging.draft.take() else {
+            return false;
+        };
+        self.staging.confirmation = None;
+        **self.preferences = draft;
+        true
+    }
+
+    /// Writes the draft into the preferences, finishing the edit.
+    /// Unless [`Self::confirm`] is called before `timeout`, the previous value is restored
+    /// and [`PreferencesReverted`] is sent.
+    /// The previous value is the one kept in storage until it's confirmed,
+    /// so it's restored on the next launch if the app exits before.
+    ///
+    /// The timeout is measured with [`Time<Real>`], added by [`TimePlugin`](bevy::time::TimePlugin).
+    /// Without it, the applied preferences are never reverted, and a warning is logged.
+    /// Returns false if there was no draft being edited.
+    pub fn apply_with_confirmation(&mut self, timeout: Duration) -> bool {
+        let Some(draft) = self.staging.draft.take() else {
+            return false;
+        };
+
+        let previous = std::mem::replace(&mut **self.preferences, draft);
+
+        // If a confirmation was already pending, the original value is the one to revert to
+        let previous = match self.staging.confirmation.take() {
+            Some(pending) => pending.previous,
+            None => previous,
+        };
+
+        self.staging.confirmation = Some(PendingConfirmation {
+            previous,
+            remaining: timeout,
+        });
+        true
+    }
+
+    /// Returns the time remaining before the applied preferences are reverted,
+    /// if there is any pending confirmation.
+    pub fn pending_confirmation(&self) -> Option<Duration> {
+        self.staging
+            .confirmation
+            .as_ref()
+            .map(|pending| pending.remaining)
+    }
+
+    /// Confirms the applied preferences, so they are not reverted, and stores them.
+    pub fn confirm(&mut self) {
+        if self.staging.confirmation.take().is_some() {
+            self.preferences.set_changed();
+        }
+    }
+
+    /// Reverts the applied preferences immediately, if there is any pending confirmation.
+    pub fn revert(&mut self) {
+        if let Some(pending) = self.staging.confirmation.take() {
+            **self.preferences = pending.previous;
+        }
+    }
+}
+
+pub(crate) fn has_pending_confirmation<T: PreferencesType>(
+    staging: Option<Res<PreferencesStaging<T>>>,
+) -> bool {
+    staging.is_some_and(|staging| staging.confirmation.is_some())
+}
+
+pub(crate) fn tick_pending_confirmation<T: PreferencesType>(
+    time: Option<Res<Time<Real>>>,
+    mut staging: ResMut<PreferencesStaging<T>>,
+    mut preferences: ResMut<PreferencesResource<T>>,
+    mut reverted: EventWriter<PreferencesReverted<T>>,
+    mut warned_missing_time: Local<bool>,
+) {
+    let Some(pending) = staging.confirmation.as_mut() else {
+        return;
+    };
+
+    let Some(time) = time else {
+        if !*warned_missing_time {
+            warn!(
+                "Preferences {} are pending confirmation, but there is no Time<Real> resource to measure the timeout, add TimePlugin",
+                T::short_type_path()
+            );
+            *warned_missing_time = true;
+        }
+        return;
+    };
+
+    pending.remaining = pending.remaining.saturating_sub(time.delta());
+
+    if pending.remaining.is_zero() {
+        let pending = staging.confirmation.take().expect("Pending confirmation");
+        let unconfirmed = std::mem::replace(&mut **preferences, pending.previous);
+        info!(
+            "Preferences {} were not confirmed in time, reverting them",
+            T::short_type_path()
+        );
+        reverted.send(PreferencesReverted { unconfirmed });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::serializable_map::PreferencesSerializableMap;
+    use crate::{
+        PreferencesResource, PreferencesReverted, RegisterPreferencesExt, StagedPreferences,
+    };
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::prelude::*;
+    use std::time::Duration;
+
+    #[derive(Reflect, Default)]
+    struct MyPreferences {
+        value: u32,
+    }
+
+    fn new_app() -> App {
+        let mut app = App::new();
+        app.register_preferences::<MyPreferences>()
+            .init_resource::<PreferencesSerializableMap>()
+            .insert_resource(Time::<Real>::default());
+        app.world_mut()
+            .resource_mut::<Time<Real>>()
+            .update_with_duration(Duration::ZERO);
+        app.update();
+        app
+    }
+
+    fn current_value(app: &App) -> u32 {
+        app.world()
+            .resource::<PreferencesResource<MyPreferences>>()
+            .value
+    }
+
+    fn stored_value(app: &App) -> u32 {
+        app.world()
+            .resource::<PreferencesSerializableMap>()
+            .get::<MyPreferences>()
+            .unwrap()
+            .value
+    }
+
+    #[test]
+    fn test_draft_is_only_stored_once_applied() {
+        let mut app = new_app();
+
+        app.world_mut()
+            .run_system_once(|mut staged: StagedPreferences<MyPreferences>| {
+                staged.begin_edit();
+                staged.draft_mut().unwrap().value = 3;
+                assert!(staged.is_draft_changed());
+            })
+            .unwrap();
+        app.update();
+
+        assert_eq!(current_value(&app), 0);
+        assert_eq!(stored_value(&app), 0);
+
+        app.world_mut()
+            .run_system_once(|mut staged: StagedPreferences<MyPreferences>| {
+                assert!(staged.apply());
+                assert!(!staged.is_editing());
+            })
+            .unwrap();
+        app.update();
+
+        assert_eq!(current_value(&app), 3);
+        assert_eq!(stored_value(&app), 3);
+    }
+
+    #[test]
+    fn test_discard_draft() {
+        let mut app = new_app();
+
+        app.world_mut()
+            .run_system_once(|mut staged: StagedPreferences<MyPreferences>| {
+                staged.begin_edit();
+                staged.draft_mut().unwrap().value = 3;
+                staged.discard();
+                assert!(!staged.apply());
+            })
+            .unwrap();
+        app.update();
+
+        assert_eq!(current_value(&app), 0);
+    }
+
+    #[test]
+    fn test_unconfirmed_preferences_are_reverted() {
+        let mut app = new_app();
+
+        app.world_mut()
+            .run_system_once(|mut staged: StagedPreferences<MyPreferences>| {
+                staged.begin_edit();
+                staged.draft_mut().unwrap().value = 3;
+                staged.apply_with_confirmation(Duration::from_secs(15));
+            })
+            .unwrap();
+
+        app.world_mut()
+            .resource_mut::<Time<Real>>()
+            .update_with_duration(Duration::from_secs(10));
+        app.update();
+        assert_eq!(current_value(&app), 3);
+        assert_eq!(stored_value(&app), 0);
+
+        app.world_mut()
+            .resource_mut::<Time<Real>>()
+            .update_with_duration(Duration::from_secs(10));
+        app.update();
+        assert_eq!(current_value(&app), 0);
+        assert_eq!(stored_value(&app), 0);
+
+        let reverted = app
+            .world()
+            .resource::<Events<PreferencesReverted<MyPreferences>>>();
+        assert_eq!(reverted.len(), 1);
+    }
+
+    #[test]
+    fn test_confirmed_preferences_are_kept() {
+        let mut app = new_app();
+
+        app.world_mut()
+            .run_system_once(|mut staged: StagedPreferences<MyPreferences>| {
+                staged.begin_edit();
+                staged.draft_mut().unwrap().value = 3;
+                staged.apply_with_confirmation(Duration::from_secs(15));
+            })
+            .unwrap();
+        app.update();
+        assert_eq!(current_value(&app), 3);
+        assert_eq!(stored_value(&app), 0);
+
+        app.world_mut()
+            .run_system_once(|mut staged: StagedPreferences<MyPreferences>| {
+                staged.confirm();
+            })
+            .unwrap();
+        app.update();
+        assert_eq!(stored_value(&app), 3);
+
+        app.world_mut()
+            .resource_mut::<Time<Real>>()
+            .update_with_duration(Duration::from_secs(20));
+        app.update();
+        assert_eq!(current_value(&app), 3);
+    }
+}
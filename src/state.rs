@@ -0,0 +1,241 @@
+use crate::plugin::LoadPreferences;
+use crate::serializable_map::PreferencesSerializableMap;
+use crate::{Preferences, PreferencesSet, PreferencesType, RegisterPreferencesExt};
+use bevy::prelude::*;
+use bevy::reflect::{
+    DynamicEnum, DynamicVariant, ReflectRef, Reflectable, TypeInfo, Typed, VariantInfo,
+};
+use bevy::state::state::{FreelyMutableState, StateTransitionEvent};
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+
+/// Preferences with the last state of every type registered with [`RegisterPreferencesExt::persist_state`].
+///
+/// States are stored by the name of their variant, so only unit variants can be persisted,
+/// and a stored variant that no longer exists falls back to the initial state.
+#[derive(Reflect, Clone, Debug, Default, PartialEq)]
+pub struct PersistedStates {
+    /// Variant name of the last state, by the type path of the state.
+    pub states: BTreeMap<String, String>,
+}
+
+impl PersistedStates {
+    /// Returns the stored state of type `S`, if its variant still exists.
+    pub fn get<S: FromReflect + Typed>(&self) -> Option<S> {
+        let variant = self.states.get(S::type_path())?;
+        let TypeInfo::Enum(enum_info) = S::type_info() else {
+            return None;
+        };
+        if !matches!(enum_info.variant(variant), Some(VariantInfo::Unit(_))) {
+            return None;
+        }
+        S::from_reflect(&DynamicEnum::new(variant, DynamicVariant::Unit))
+    }
+
+    /// Stores the variant name of `state`.
+    /// Returns false if nothing was stored because `state` is not a unit variant of an enum.
+    pub fn set<S: Reflect + TypePath>(&mut self, state: &S) -> bool {
+        let ReflectRef::Enum(state) = state.reflect_ref() else {
+            return false;
+        };
+        if state.field_len() > 0 {
+            return false;
+        }
+        let variant = state.variant_name();
+        if self.states.get(S::type_path()).map(String::as_str) != Some(variant) {
+            self.states
+                .insert(S::type_path().to_string(), variant.to_string());
+        }
+        true
+    }
+}
+
+pub(crate) struct PersistStatePlugin<S>(PhantomData<S>);
+
+impl<S> Default for PersistStatePlugin<S> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<S> Plugin for PersistStatePlugin<S>
+where
+    S: FreelyMutableState + Reflectable + PreferencesType,
+{
+    fn build(&self, app: &mut App) {
+        app.register_preferences::<PersistedStates>()
+            .add_systems(
+                LoadPreferences,
+                restore_state::<S>.after(PreferencesSet::Load),
+            )
+            .add_systems(
+                Last,
+                store_state::<S>.before(PreferencesSet::SetReflectMapValues),
+            );
+    }
+}
+
+// Replaces the initial state before the first transition, that runs right after loading the preferences
+fn restore_state<S: FreelyMutableState + FromReflect + Typed>(world: &mut World) {
+    let Some(mut storage_map) = world.get_resource_mut::<PreferencesSerializableMap>() else {
+        return;
+    };
+    storage_map
+        .bypass_change_detection()
+        .resolve_sparse(&PersistedStates::default());
+    let Some(persisted_states) = storage_map.get::<PersistedStates>() else {
+        return;
+    };
+
+    let Some(state) = persisted_states.get::<S>() else {
+        if persisted_states.states.contains_key(S::type_path()) {
+            warn!(
+                "Stored state {} does not exist anymore, using the initial state",
+                S::short_type_path()
+            );
+        }
+        return;
+    };
+
+    let Some(mut transitions) = world.get_resource_mut::<Events<StateTransitionEvent<S>>>() else {
+        warn!(
+            "State {} is not initialized, so it's not restored",
+            S::short_type_path()
+        );
+        return;
+    };
+
+    debug!("Restoring state {state:?}");
+    transitions.clear();
+    transitions.send(StateTransitionEvent {
+        exited: None,
+        entered: Some(state.clone()),
+    });
+    world.insert_resource(State::new(state));
+}
+
+fn store_state<S: FreelyMutableState + PreferencesType + Typed>(
+    mut transitions: EventReader<StateTransitionEvent<S>>,
+    mut preferences: Preferences<PersistedStates>,
+) {
+    let Some(state) = transitions
+        .read()
+        .filter_map(|transition| transition.entered.as_ref())
+        .last()
+    else {
+        return;
+    };
+
+    let is_unchanged = preferences.get::<S>().as_ref() == Some(state);
+    if !is_unchanged && !preferences.set(state) {
+        warn!(
+            "State {} is not a unit variant of an enum, so it can't be persisted",
+            S::short_type_path()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        PersistedStates, PreferencesPlugin, PreferencesStorageType, RegisterPreferencesExt,
+    };
+    use bevy::core::{FrameCount, FrameCountPlugin};
+    use bevy::prelude::*;
+    use bevy::state::app::StatesPlugin;
+    use bevy::time::TimePlugin;
+    use std::path::Path;
+
+    #[derive(States, Reflect, Clone, Debug, Default, PartialEq, Eq, Hash)]
+    enum MenuTab {
+        #[default]
+        General,
+        Audio,
+        Video,
+    }
+
+    #[derive(Resource, Default)]
+    struct Entered(Vec<MenuTab>);
+
+    fn record_entered(tab: MenuTab) -> impl Fn(ResMut<Entered>) {
+        move |mut entered| entered.0.push(tab.clone())
+    }
+
+    fn run_app(path: &Path, tab: Option<MenuTab>) -> Vec<MenuTab> {
+        let mut app = App::new();
+        app.add_plugins((TimePlugin, FrameCountPlugin, StatesPlugin))
+            .add_plugins(
+                PreferencesPlugin::persisted_with_app_name("PreferencesTest").with_storage_type(
+                    PreferencesStorageType::FileSystemWithParentDirectory(path.into()),
+                ),
+            )
+            .init_state::<MenuTab>()
+            .persist_state::<MenuTab>()
+            .init_resource::<Entered>()
+            .add_systems(OnEnter(MenuTab::General), record_entered(MenuTab::General))
+            .add_systems(OnEnter(MenuTab::Audio), record_entered(MenuTab::Audio))
+            .add_systems(Update, move |mut next_state: ResMut<NextState<MenuTab>>| {
+                if let Some(tab) = &tab {
+                    next_state.set(tab.clone());
+                }
+            })
+            .add_systems(
+                PostUpdate,
+                |mut app_exit: EventWriter<AppExit>, frame_count: Res<FrameCount>| {
+                    if frame_count.0 > 0 {
+                        app_exit.send_default();
+                    }
+                },
+            );
+        app.update();
+        app.update();
+        app.world_mut().remove_resource::<Entered>().unwrap().0
+    }
+
+    #[test]
+    fn test_state_is_restored_before_the_first_transition() {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        let entered = run_app(temp_dir.path(), Some(MenuTab::Audio));
+        assert_eq!(entered, [MenuTab::General, MenuTab::Audio]);
+
+        let entered = run_app(temp_dir.path(), None);
+        assert_eq!(entered, [MenuTab::Audio]);
+    }
+
+    #[test]
+    fn test_missing_variants_are_not_restored() {
+        let persisted_states = PersistedStates {
+            states: [(MenuTab::type_path().to_string(), "Graphics".to_string())].into(),
+        };
+        assert_eq!(persisted_states.get::<MenuTab>(), None);
+
+        let mut persisted_states = PersistedStates::default();
+        assert!(persisted_states.set(&MenuTab::Video));
+        assert_eq!(persisted_states.get::<MenuTab>(), Some(MenuTab::Video));
+    }
+
+    mod editor {
+        use bevy::prelude::*;
+
+        #[derive(States, Reflect, Clone, Debug, Default, PartialEq, Eq, Hash)]
+        pub enum MenuTab {
+            #[default]
+            Scene,
+            Assets,
+        }
+    }
+
+    #[test]
+    fn test_states_with_the_same_name_are_stored_separately() {
+        let mut persisted_states = PersistedStates::default();
+        assert!(persisted_states.set(&MenuTab::Audio));
+        assert!(persisted_states.set(&editor::MenuTab::Assets));
+
+        assert_eq!(persisted_states.get::<MenuTab>(), Some(MenuTab::Audio));
+        assert_eq!(
+            persisted_states.get::<editor::MenuTab>(),
+            Some(editor::MenuTab::Assets)
+        );
+    }
+}
@@ -132,6 +132,10 @@ impl FileStorage {
         Ok(Self { path, format })
     }
 
+    fn side_data_path(&self, key: &str) -> PathBuf {
+        self.path.with_file_name(key)
+    }
+
     #[cfg(test)]
     pub(crate) fn new_from_format<F: FileStorageFormat>(
         parent_path: impl Into<PathBuf>,
@@ -156,12 +160,36 @@ impl PreferencesStorage for FileStorage {
     }
 
     fn save_preferences(&self, map: &PreferencesSerializableMap) -> Result<()> {
-        debug!("Storing preferences to {}", self.path.display());
-
         let output = (self.format.serialize_preferences)(map)?;
+
+        if std::fs::read(&self.path).is_ok_and(|contents| contents == output.as_bytes()) {
+            debug!("Preferences in {} are up to date", self.path.display());
+            return Ok(());
+        }
+
+        debug!("Storing preferences to {}", self.path.display());
         write_atomically(&self.path, output)?;
         Ok(())
     }
+
+    fn load_side_data(&self, key: &str) -> Result<Option<String>> {
+        match std::fs::read_to_string(self.side_data_path(key)) {
+            Ok(contents) => Ok(Some(contents)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn save_side_data(&self, key: &str, data: &str) -> Result<()> {
+        let path = self.side_data_path(key);
+        if std::fs::read(&path).is_ok_and(|contents| contents == data.as_bytes()) {
+            return Ok(());
+        }
+
+        debug!("Storing {key} to {}", path.display());
+        write_atomically(&path, data)?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -221,4 +249,49 @@ mod tests {
 
         assert_eq!(read_map, written_map);
     }
+
+    #[test]
+    #[cfg(unix)]
+    fn fs_does_not_rewrite_identical_contents() {
+        use std::os::unix::fs::MetadataExt;
+
+        // Every write replaces the file with a new one, so its inode changes
+        fn inode(storage: &FileStorage) -> u64 {
+            std::fs::metadata(&storage.path).unwrap().ino()
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+        let registry = get_registry();
+
+        let storage = FileStorage::new(temp_dir.path()).unwrap();
+
+        let mut map = PreferencesSerializableMap::empty(registry);
+        map.set(Bar("Bar".into()));
+
+        storage.save_preferences(&map).unwrap();
+        let first_inode = inode(&storage);
+
+        storage.save_preferences(&map).unwrap();
+        assert_eq!(inode(&storage), first_inode);
+
+        map.set(Bar("Baz".into()));
+        storage.save_preferences(&map).unwrap();
+        assert_ne!(inode(&storage), first_inode);
+    }
+
+    #[test]
+    fn fs_writes_and_reads_side_data() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorage::new(temp_dir.path()).unwrap();
+
+        assert_eq!(storage.load_side_data("side.ron").unwrap(), None);
+
+        storage.save_side_data("side.ron", "(value: 3)").unwrap();
+
+        assert_eq!(
+            storage.load_side_data("side.ron").unwrap().as_deref(),
+            Some("(value: 3)")
+        );
+        assert!(temp_dir.path().join("side.ron").exists());
+    }
 }
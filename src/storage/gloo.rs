@@ -1,5 +1,6 @@
 use crate::serializable_map::{PreferencesSerializableMap, PreferencesSerializableMapSeed};
 use bevy::log::*;
+use gloo_utils::errors::JsError;
 use serde::de::DeserializeSeed;
 
 use crate::Result;
@@ -17,6 +18,10 @@ pub(crate) struct GlooStorage {
     load_preferences:
         fn(&str, PreferencesSerializableMapSeed) -> Result<PreferencesSerializableMap>,
     save_preferences: fn(&str, map: &PreferencesSerializableMap) -> Result<()>,
+    load_side_data: fn(&str) -> Result<Option<String>>,
+    save_side_data: fn(&str, &str) -> Result<()>,
+    copy_items: fn(&str, &str, &str) -> Result<()>,
+    remove_items: fn(&str) -> Result<()>,
 }
 
 impl GlooStorage {
@@ -29,6 +34,10 @@ impl GlooStorage {
             preferences_key: preferences_key.into(),
             load_preferences: load_preferences::<T>,
             save_preferences: save_preferences::<T>,
+            load_side_data: load_side_data::<T>,
+            save_side_data: save_side_data::<T>,
+            copy_items: copy_items::<T>,
+            remove_items: remove_items::<T>,
         }
     }
 
@@ -56,6 +65,64 @@ impl PreferencesStorage for GlooStorage {
         debug!("Saved preferences on {:?}Storage", self.storage_type);
         Ok(())
     }
+
+    fn load_side_data(&self, key: &str) -> Result<Option<String>> {
+        (self.load_side_data)(&self.side_data_key(key))
+    }
+
+    fn save_side_data(&self, key: &str, data: &str) -> Result<()> {
+        (self.save_side_data)(&self.side_data_key(key), data)?;
+        debug!("Saved {key} on {:?}Storage", self.storage_type);
+        Ok(())
+    }
+}
+
+impl GlooStorage {
+    fn side_data_key(&self, key: &str) -> String {
+        format!("{}_{key}", self.preferences_key)
+    }
+
+    /// Copies the preferences and every side data into `other`, except the side data `skipped_key`.
+    pub(crate) fn copy_to(&self, other: &GlooStorage, skipped_key: &str) -> Result<()> {
+        (self.copy_items)(
+            &self.preferences_key,
+            &other.preferences_key,
+            &self.side_data_key(skipped_key),
+        )
+    }
+
+    /// Removes the preferences and every side data.
+    pub(crate) fn remove(&self) -> Result<()> {
+        (self.remove_items)(&self.preferences_key)
+    }
+}
+
+// Keys of the preferences stored under `preferences_key`, and their side data
+fn stored_keys<T: gloo_storage::Storage>(preferences_key: &str) -> Vec<String> {
+    let raw = T::raw();
+    let side_data_prefix = format!("{preferences_key}_");
+    (0..T::length())
+        .filter_map(|index| raw.key(index).ok().flatten())
+        .filter(|key| key == preferences_key || key.starts_with(&side_data_prefix))
+        .collect()
+}
+
+fn copy_items<T: gloo_storage::Storage>(from: &str, to: &str, skipped_key: &str) -> Result<()> {
+    for key in stored_keys::<T>(from) {
+        if key == skipped_key {
+            continue;
+        }
+        let value = T::get::<serde_json::Value>(&key)?;
+        T::set(format!("{to}{}", &key[from.len()..]), value)?;
+    }
+    Ok(())
+}
+
+fn remove_items<T: gloo_storage::Storage>(preferences_key: &str) -> Result<()> {
+    for key in stored_keys::<T>(preferences_key) {
+        T::delete(key);
+    }
+    Ok(())
 }
 
 fn load_preferences<T: gloo_storage::Storage>(
@@ -69,7 +136,42 @@ fn save_preferences<T: gloo_storage::Storage>(
     key: &str,
     map: &PreferencesSerializableMap,
 ) -> Result<()> {
-    T::set(key, map)?;
+    let output = serde_json::to_string(map).map_err(gloo_storage::errors::StorageError::from)?;
+
+    let current = T::raw().get_item(key).map_err(storage_error)?;
+
+    if current.as_deref() == Some(output.as_str()) {
+        return Ok(());
+    }
+
+    T::raw().set_item(key, &output).map_err(storage_error)?;
+    Ok(())
+}
+
+// Storage methods throw errors, like a `SecurityError` when the access to the storage is denied
+fn storage_error<E>(err: E) -> gloo_storage::errors::StorageError
+where
+    JsError: TryFrom<E>,
+    <JsError as TryFrom<E>>::Error: std::fmt::Debug,
+{
+    let err = JsError::try_from(err).expect("unreachable: storage methods only throw errors");
+    gloo_storage::errors::StorageError::JsError(err)
+}
+
+fn load_side_data<T: gloo_storage::Storage>(key: &str) -> Result<Option<String>> {
+    match T::get::<String>(key) {
+        Ok(data) => Ok(Some(data)),
+        Err(gloo_storage::errors::StorageError::KeyNotFound(_)) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn save_side_data<T: gloo_storage::Storage>(key: &str, data: &str) -> Result<()> {
+    if T::get::<String>(key).is_ok_and(|current| current == data) {
+        return Ok(());
+    }
+
+    T::set(key, data)?;
     Ok(())
 }
 
@@ -96,7 +198,7 @@ where
         let key = key.as_ref();
         let item_string = T::raw()
             .get_item(key)
-            .expect("unreachable: get_item does not throw an exception")
+            .map_err(storage_error)?
             .ok_or_else(|| gloo_storage::errors::StorageError::KeyNotFound(key.to_string()))?;
 
         let mut deserializer = serde_json::de::Deserializer::from_reader(item_string.as_bytes());
@@ -26,6 +26,20 @@ pub trait PreferencesStorage: Send + Sync + 'static {
 
     /// Saves the preferences
     fn save_preferences(&self, map: &PreferencesSerializableMap) -> Result<()>;
+
+    /// Loads data stored next to the preferences under `key`, for data that should not be part of the preferences,
+    /// like UI state. Returns `None` if nothing is stored, or if the storage does not support it.
+    fn load_side_data(&self, key: &str) -> Result<Option<String>> {
+        let _ = key;
+        Ok(None)
+    }
+
+    /// Saves data next to the preferences under `key`. See [`Self::load_side_data`].
+    /// Does nothing by default.
+    fn save_side_data(&self, key: &str, data: &str) -> Result<()> {
+        let _ = (key, data);
+        Ok(())
+    }
 }
 
 /// Represents the current Preferences storage used.
@@ -0,0 +1,603 @@
+use crate::changes::field_path;
+use crate::defaults::reset_field;
+use crate::{PreferencesType, clone_preferences};
+use bevy::prelude::*;
+use bevy::reflect::attributes::CustomAttributes;
+use bevy::reflect::{ReflectPath, ReflectRef, TypeInfo, VariantInfo};
+use std::marker::PhantomData;
+
+/// Field attribute that restricts a numeric field to an inclusive range.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_simple_preferences::*;
+/// #[derive(Reflect, Default)]
+/// struct AudioPreferences {
+///     #[reflect(@PreferencesRange::new(0.0, 1.0))]
+///     volume: f32,
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy, Debug, PartialEq)]
+pub struct PreferencesRange {
+    /// Minimum allowed value.
+    pub min: f64,
+    /// Maximum allowed value.
+    pub max: f64,
+}
+
+impl PreferencesRange {
+    /// Creates a range between `min` and `max`, both included.
+    pub const fn new(min: f64, max: f64) -> Self {
+        Self { min, max }
+    }
+}
+
+/// Field attribute that restricts an enum field to a subset of its variants.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_simple_preferences::*;
+/// #[derive(Reflect, Default)]
+/// enum Quality {
+///     Low,
+///     #[default]
+///     Medium,
+///     High,
+///     Experimental,
+/// }
+///
+/// #[derive(Reflect, Default)]
+/// struct GraphicsPreferences {
+///     #[reflect(@AllowedVariants::new(["Low", "Medium", "High"]))]
+///     quality: Quality,
+/// }
+/// ```
+#[derive(Reflect, Clone, Debug, PartialEq)]
+pub struct AllowedVariants(pub Vec<String>);
+
+impl AllowedVariants {
+    /// Creates the list of allowed variant names.
+    pub fn new(variants: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self(variants.into_iter().map(Into::into).collect())
+    }
+}
+
+/// Field attribute that requires a string, list, map or set field to not be empty.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_simple_preferences::*;
+/// #[derive(Reflect, Default)]
+/// struct ProfilePreferences {
+///     #[reflect(@NonEmpty)]
+///     player_name: String,
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy, Debug, PartialEq)]
+pub struct NonEmpty;
+
+/// What to do when the preferences are not valid.
+///
+/// Values are never replaced by a default value that is not valid either.
+/// In that case, the invalid value is kept, and the failure is still reported.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PreferencesValidationStrategy {
+    /// Numeric fields out of range are clamped, and any other invalid field is reset
+    /// to its default value. If a custom validator fails, the value is rejected.
+    #[default]
+    Clamp,
+    /// The invalid value is discarded, and the previous value is kept.
+    /// If there is no previous value, because they have just been loaded, the default value is used.
+    Reject,
+    /// The invalid fields are reset to their default value.
+    /// If a custom validator fails, the whole preferences are reset.
+    ResetToDefault,
+}
+
+/// Problem found while validating preferences.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PreferencesValidationIssue {
+    /// Path of the invalid field, as in [`bevy::reflect::GetPath`].
+    /// It's empty for the problems found by custom validators.
+    pub path: String,
+    /// Description of the problem.
+    pub message: String,
+    fix: Option<IssueFix>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum IssueFix {
+    Clamp(PreferencesRange),
+    ResetField,
+}
+
+/// Event sent when invalid preferences of type `T` are found, either when loading or changing them.
+#[derive(Event)]
+pub struct PreferencesValidationFailed<T> {
+    /// Problems found in the preferences.
+    pub issues: Vec<PreferencesValidationIssue>,
+    /// Strategy used to fix them.
+    pub strategy: PreferencesValidationStrategy,
+    marker: PhantomData<fn() -> T>,
+}
+
+type Validator<T> = Box<dyn Fn(&mut T) -> Result<(), String> + Send + Sync>;
+
+/// Validators of the preferences of type `T`.
+/// See [`crate::RegisterPreferencesExt::add_preferences_validator`].
+#[derive(Resource)]
+pub(crate) struct PreferencesValidators<T> {
+    pub strategy: PreferencesValidationStrategy,
+    validators: Vec<Validator<T>>,
+}
+
+impl<T> Default for PreferencesValidators<T> {
+    fn default() -> Self {
+        Self {
+            strategy: PreferencesValidationStrategy::default(),
+            validators: Vec::new(),
+        }
+    }
+}
+
+impl<T: PreferencesType> PreferencesValidators<T> {
+    pub fn add(
+        &mut self,
+        validator: impl Fn(&mut T) -> Result<(), String> + Send + Sync + 'static,
+    ) {
+        self.validators.push(Box::new(validator));
+    }
+
+    fn find_issues(&self, value: &mut T) -> Vec<PreferencesValidationIssue> {
+        let mut issues = Vec::new();
+        check_attributes(value.as_partial_reflect(), "", &mut issues);
+
+        for validator in &self.validators {
+            if let Err(message) = validator(value) {
+                issues.push(PreferencesValidationIssue {
+                    path: String::new(),
+                    message,
+                    fix: None,
+                });
+            }
+        }
+        issues
+    }
+
+    /// Validates `value`, fixing it according to the strategy.
+    /// `previous` is the last valid value, if there is any.
+    pub fn validate(
+        &self,
+        value: &mut T,
+        default_value: &T,
+        previous: Option<&T>,
+    ) -> Option<PreferencesValidationFailed<T>> {
+        let issues = self.find_issues(value);
+        if issues.is_empty() {
+            return None;
+        }
+
+        let is_fixable = issues.iter().all(|issue| issue.fix.is_some());
+        match self.strategy {
+            PreferencesValidationStrategy::Clamp if is_fixable => {
+                self.fix_fields(value, default_value, &issues, false);
+            }
+            PreferencesValidationStrategy::ResetToDefault if is_fixable => {
+                self.fix_fields(value, default_value, &issues, true);
+            }
+            PreferencesValidationStrategy::Clamp | PreferencesValidationStrategy::Reject => {
+                self.replace_value(value, previous.unwrap_or(default_value));
+            }
+            PreferencesValidationStrategy::ResetToDefault => {
+                self.replace_value(value, default_value);
+            }
+        }
+
+        for issue in &issues {
+            warn!(
+                "Invalid preferences {} {}: {}",
+                T::short_type_path(),
+                issue.path,
+                issue.message
+            );
+        }
+
+        Some(PreferencesValidationFailed {
+            issues,
+            strategy: self.strategy,
+            marker: PhantomData,
+        })
+    }
+
+    // Fixes only the invalid fields, unless their default value is invalid too
+    fn fix_fields(
+        &self,
+        value: &mut T,
+        default_value: &T,
+        issues: &[PreferencesValidationIssue],
+        reset: bool,
+    ) {
+        let default_issues = self.find_issues(&mut clone_preferences(default_value));
+        for issue in issues {
+            let fix = match issue.fix {
+                Some(IssueFix::Clamp(_)) if reset => Some(IssueFix::ResetField),
+                fix => fix,
+            };
+            if fix == Some(IssueFix::ResetField)
+                && default_issues
+                    .iter()
+                    .any(|default_issue| default_issue.path == issue.path)
+            {
+                error!(
+                    "Default value of preferences {} {} is invalid too, keeping the invalid value",
+                    T::short_type_path(),
+                    issue.path
+                );
+                continue;
+            }
+            fix_issue(
+                value.as_partial_reflect_mut(),
+                default_value,
+                &issue.path,
+                fix,
+            );
+        }
+    }
+
+    // Replaces the whole value, unless the replacement is invalid too
+    fn replace_value(&self, value: &mut T, replacement: &T) {
+        let mut replacement = clone_preferences(replacement);
+        if self.find_issues(&mut replacement).is_empty() {
+            *value = replacement;
+        } else {
+            error!(
+                "Preferences {} can't be replaced by an invalid value, keeping the invalid value",
+                T::short_type_path()
+            );
+        }
+    }
+}
+
+fn fix_issue<T: PreferencesType>(
+    value: &mut dyn PartialReflect,
+    default_value: &T,
+    path: &str,
+    fix: Option<IssueFix>,
+) {
+    match fix {
+        Some(IssueFix::Clamp(range)) => {
+            if let Ok(field) = path.reflect_element_mut(value) {
+                if let Some(number) = as_f64(field) {
+                    let clamped = if number.is_nan() {
+                        range.min
+                    } else {
+                        number.clamp(range.min, range.max)
+                    };
+                    set_f64(field, clamped);
+                }
+            }
+        }
+        Some(IssueFix::ResetField) => {
+            if let Err(err) = reset_field(value, default_value.as_partial_reflect(), path) {
+                error!("Error resetting invalid field: {err}");
+            }
+        }
+        None => {}
+    }
+}
+
+macro_rules! impl_numeric_conversions {
+    ($($ty:ty),*) => {
+        pub(crate) fn as_f64(value: &dyn PartialReflect) -> Option<f64> {
+            $(
+                if let Some(value) = value.try_downcast_ref::<$ty>() {
+                    return Some(*value as f64);
+                }
+            )*
+            None
+        }
+
+        pub(crate) fn set_f64(value: &mut dyn PartialReflect, new_value: f64) {
+            $(
+                if let Some(value) = value.try_downcast_mut::<$ty>() {
+                    *value = new_value as $ty;
+                    return;
+                }
+            )*
+        }
+    };
+}
+
+impl_numeric_conversions!(
+    f32, f64, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize
+);
+
+fn check_attributes(
+    value: &dyn PartialReflect,
+    path: &str,
+    issues: &mut Vec<PreferencesValidationIssue>,
+) {
+    let type_info = value.get_represented_type_info();
+
+    match value.reflect_ref() {
+        ReflectRef::Struct(value) => {
+            for (index, field) in value.iter_fields().enumerate() {
+                let name = value.name_at(index).expect("Field index out of bounds");
+                let field_path = field_path(path, name);
+                if let Some(TypeInfo::Struct(info)) = type_info {
+                    if let Some(field_info) = info.field(name) {
+                        check_field(field, field_info.custom_attributes(), &field_path, issues);
+                    }
+                }
+                check_attributes(field, &field_path, issues);
+            }
+        }
+        ReflectRef::TupleStruct(value) => {
+            for (index, field) in value.iter_fields().enumerate() {
+                let field_path = field_path(path, index);
+                if let Some(TypeInfo::TupleStruct(info)) = type_info {
+                    if let Some(field_info) = info.field_at(index) {
+                        check_field(field, field_info.custom_attributes(), &field_path, issues);
+                    }
+                }
+                check_attributes(field, &field_path, issues);
+            }
+        }
+        ReflectRef::Enum(value) => {
+            let variant_info = match type_info {
+                Some(TypeInfo::Enum(info)) => info.variant(value.variant_name()),
+                _ => None,
+            };
+            for (index, field) in value.iter_fields().enumerate() {
+                let (field_path, custom_attributes) = match (field.name(), variant_info) {
+                    (Some(name), Some(VariantInfo::Struct(info))) => (
+                        field_path(path, name),
+                        info.field(name).map(|field| field.custom_attributes()),
+                    ),
+                    (_, Some(VariantInfo::Tuple(info))) => (
+                        field_path(path, index),
+                        info.field_at(index).map(|field| field.custom_attributes()),
+                    ),
+                    (Some(name), _) => (field_path(path, name), None),
+                    (None, _) => (field_path(path, index), None),
+                };
+                if let Some(custom_attributes) = custom_attributes {
+                    check_field(field.value(), custom_attributes, &field_path, issues);
+                }
+                check_attributes(field.value(), &field_path, issues);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn check_field(
+    value: &dyn PartialReflect,
+    custom_attributes: &CustomAttributes,
+    path: &str,
+    issues: &mut Vec<PreferencesValidationIssue>,
+) {
+    if let Some(range) = custom_attributes.get::<PreferencesRange>() {
+        if let Some(number) = as_f64(value) {
+            if !(range.min..=range.max).contains(&number) {
+                issues.push(PreferencesValidationIssue {
+                    path: path.to_owned(),
+                    message: format!("{number} is not between {} and {}", range.min, range.max),
+                    fix: Some(IssueFix::Clamp(*range)),
+                });
+            }
+        }
+    }
+
+    if let Some(allowed_variants) = custom_attributes.get::<AllowedVariants>() {
+        if let ReflectRef::Enum(value) = value.reflect_ref() {
+            let variant_name = value.variant_name();
+            if !allowed_variants
+                .0
+                .iter()
+                .any(|allowed| allowed == variant_name)
+            {
+                issues.push(PreferencesValidationIssue {
+                    path: path.to_owned(),
+                    message: format!("{variant_name} is not one of {:?}", allowed_variants.0),
+                    fix: Some(IssueFix::ResetField),
+                });
+            }
+        }
+    }
+
+    if custom_attributes.contains::<NonEmpty>() {
+        let is_empty = match value.reflect_ref() {
+            ReflectRef::List(list) => list.is_empty(),
+            ReflectRef::Array(array) => array.is_empty(),
+            ReflectRef::Map(map) => map.is_empty(),
+            ReflectRef::Set(set) => set.is_empty(),
+            _ => value
+                .try_downcast_ref::<String>()
+                .is_some_and(|string| string.is_empty()),
+        };
+        if is_empty {
+            issues.push(PreferencesValidationIssue {
+                path: path.to_owned(),
+                message: "can not be empty".to_owned(),
+                fix: Some(IssueFix::ResetField),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::serializable_map::PreferencesSerializableMap;
+    use crate::{
+        AllowedVariants, NonEmpty, Preferences, PreferencesRange, PreferencesResource,
+        PreferencesValidationFailed, PreferencesValidationStrategy, RegisterPreferencesExt,
+    };
+    use bevy::prelude::*;
+
+    #[derive(Reflect, Default, Clone, PartialEq, Debug)]
+    enum Quality {
+        Low,
+        #[default]
+        Medium,
+        Experimental,
+    }
+
+    #[derive(Reflect, Clone, PartialEq, Debug)]
+    struct Settings {
+        #[reflect(@PreferencesRange::new(0.0, 1.0))]
+        volume: f32,
+        #[reflect(@AllowedVariants::new(["Low", "Medium"]))]
+        quality: Quality,
+        #[reflect(@NonEmpty)]
+        name: String,
+    }
+
+    impl Default for Settings {
+        fn default() -> Self {
+            Self {
+                volume: 0.5,
+                quality: Quality::Medium,
+                name: "Player".into(),
+            }
+        }
+    }
+
+    fn invalid_settings() -> Settings {
+        Settings {
+            volume: 7.0,
+            quality: Quality::Experimental,
+            name: String::new(),
+        }
+    }
+
+    fn current(app: &App) -> &Settings {
+        app.world().resource::<PreferencesResource<Settings>>()
+    }
+
+    fn failed_issues(app: &App) -> Vec<String> {
+        let events = app
+            .world()
+            .resource::<Events<PreferencesValidationFailed<Settings>>>();
+        let mut reader = events.get_cursor();
+        reader
+            .read(events)
+            .flat_map(|event| event.issues.iter().map(|issue| issue.path.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_invalid_loaded_values_are_clamped() {
+        let mut app = App::new();
+        app.register_preferences::<Settings>();
+
+        let mut reflect_map = {
+            let type_registry_arc = app.world().resource::<AppTypeRegistry>().0.clone();
+            PreferencesSerializableMap::empty(type_registry_arc)
+        };
+        reflect_map.set(invalid_settings());
+        app.insert_resource(reflect_map);
+        app.update();
+
+        let expected = Settings {
+            volume: 1.0,
+            ..Default::default()
+        };
+        assert_eq!(current(&app), &expected);
+        assert_eq!(
+            app.world()
+                .resource::<PreferencesSerializableMap>()
+                .get::<Settings>(),
+            Some(&expected)
+        );
+        assert_eq!(failed_issues(&app), ["volume", "quality", "name"]);
+    }
+
+    #[test]
+    fn test_invalid_changes_are_rejected() {
+        let mut app = App::new();
+        app.register_preferences::<Settings>()
+            .set_preferences_validation_strategy::<Settings>(PreferencesValidationStrategy::Reject);
+        app.update();
+
+        app.world_mut()
+            .resource_mut::<PreferencesResource<Settings>>()
+            .volume = 0.8;
+        app.update();
+
+        app.world_mut()
+            .resource_mut::<PreferencesResource<Settings>>()
+            .volume = -1.0;
+        app.update();
+
+        assert_eq!(current(&app).volume, 0.8);
+        assert_eq!(failed_issues(&app), ["volume"]);
+    }
+
+    #[test]
+    fn test_custom_validator_resets_to_default() {
+        let mut app = App::new();
+        app.register_preferences::<Settings>()
+            .add_preferences_validator(|settings: &mut Settings| {
+                if settings.name == "Admin" {
+                    return Err("Reserved name".into());
+                }
+                Ok(())
+            })
+            .set_preferences_validation_strategy::<Settings>(
+                PreferencesValidationStrategy::ResetToDefault,
+            )
+            .add_systems(Update, |mut settings: Preferences<Settings>| {
+                settings.volume = 0.1;
+                settings.name = "Admin".into();
+            });
+        app.update();
+
+        assert_eq!(current(&app), &Settings::default());
+        assert_eq!(failed_issues(&app), [""]);
+    }
+
+    #[test]
+    fn test_invalid_default_value_does_not_replace_changes() {
+        let mut app = App::new();
+        app.register_preferences_with_default_value(Settings {
+            name: String::new(),
+            ..Default::default()
+        })
+        .set_preferences_validation_strategy::<Settings>(
+            PreferencesValidationStrategy::ResetToDefault,
+        );
+        app.update();
+        assert_eq!(failed_issues(&app), ["name"]);
+
+        app.world_mut()
+            .resource_mut::<PreferencesResource<Settings>>()
+            .volume = 0.8;
+        app.update();
+        assert_eq!(current(&app).volume, 0.8);
+        assert_eq!(failed_issues(&app), ["name"]);
+
+        // The kept value is not validated again on every frame
+        app.update();
+        app.update();
+        assert!(failed_issues(&app).is_empty());
+    }
+
+    #[test]
+    fn test_reset_to_default_only_resets_invalid_fields() {
+        let mut app = App::new();
+        app.register_preferences::<Settings>()
+            .set_preferences_validation_strategy::<Settings>(
+                PreferencesValidationStrategy::ResetToDefault,
+            )
+            .add_systems(Update, |mut settings: Preferences<Settings>| {
+                settings.volume = 7.0;
+                settings.name = "Admin".into();
+            });
+        app.update();
+
+        let expected = Settings {
+            name: "Admin".into(),
+            ..Default::default()
+        };
+        assert_eq!(current(&app), &expected);
+        assert_eq!(failed_issues(&app), ["volume"]);
+    }
+}
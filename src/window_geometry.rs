@@ -0,0 +1,546 @@
+use crate::plugin::LoadPreferences;
+use crate::registry::RegisterMapTypesExt;
+use crate::serializable_map::PreferencesSerializableMap;
+use crate::{Preferences, PreferencesRef, PreferencesSet, RegisterPreferencesExt};
+use bevy::prelude::*;
+use bevy::window::{
+    Monitor, MonitorSelection, PrimaryMonitor, PrimaryWindow, WindowMode, WindowPosition,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+const PRIMARY_WINDOW_KEY: &str = "primary";
+
+/// Plugin that persists the mode, position, size and monitor of the primary window,
+/// and of any window with a [`PersistWindowGeometry`] component.
+///
+/// Restored positions are clamped onto the monitors that currently exist,
+/// and changes are only written into the preferences once the window stops changing for [`Self::debounce`].
+///
+/// Windows that exist when the preferences are loaded, like the primary window, are restored before [`Startup`],
+/// and the ones spawned later before they're created. Winit creates the primary window before the preferences
+/// are loaded, so it can briefly show its default geometry. To avoid it, disable the primary window of
+/// [`bevy::window::WindowPlugin`] and spawn it with [`PrimaryWindow`] in [`Startup`].
+///
+/// Requires the `window` feature.
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_simple_preferences::*;
+/// App::new()
+///     .add_plugins(DefaultPlugins)
+///     .add_plugins(PreferencesPlugin::persisted_with_app_name("MyApp"))
+///     .add_plugins(PreferencesWindowGeometryPlugin::default());
+/// ```
+pub struct PreferencesWindowGeometryPlugin {
+    /// Time a window needs to stay unchanged before its geometry is written into the preferences.
+    /// Half a second by default.
+    pub debounce: Duration,
+}
+
+impl Default for PreferencesWindowGeometryPlugin {
+    fn default() -> Self {
+        Self {
+            debounce: Duration::from_millis(500),
+        }
+    }
+}
+
+impl Plugin for PreferencesWindowGeometryPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_map_types::<String, WindowGeometry>()
+            .register_preferences::<WindowGeometryPreferences>()
+            .insert_resource(WindowGeometryDebounce {
+                debounce: self.debounce,
+                pending: HashMap::new(),
+            })
+            .add_systems(
+                LoadPreferences,
+                restore_loaded_window_geometry.after(PreferencesSet::Load),
+            )
+            .add_systems(PreUpdate, restore_window_geometry)
+            .add_systems(PostUpdate, track_window_geometry);
+    }
+}
+
+/// Component that persists the geometry of a window under a key, which needs to be stable across runs.
+/// See [`PreferencesWindowGeometryPlugin`].
+///
+/// The primary window is always persisted, using the `primary` key unless it has this component.
+#[derive(Component, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PersistWindowGeometry(pub String);
+
+/// Window mode, without the monitor, that is stored separately in [`WindowGeometry::monitor`].
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PersistedWindowMode {
+    /// See [`WindowMode::Windowed`].
+    #[default]
+    Windowed,
+    /// See [`WindowMode::BorderlessFullscreen`].
+    BorderlessFullscreen,
+    /// See [`WindowMode::SizedFullscreen`].
+    SizedFullscreen,
+    /// See [`WindowMode::Fullscreen`].
+    Fullscreen,
+}
+
+impl PersistedWindowMode {
+    fn window_mode(self, monitor_selection: MonitorSelection) -> WindowMode {
+        match self {
+            Self::Windowed => WindowMode::Windowed,
+            Self::BorderlessFullscreen => WindowMode::BorderlessFullscreen(monitor_selection),
+            Self::SizedFullscreen => WindowMode::SizedFullscreen(monitor_selection),
+            Self::Fullscreen => WindowMode::Fullscreen(monitor_selection),
+        }
+    }
+}
+
+/// Persisted geometry of a window.
+#[derive(Reflect, Clone, Debug, Default, PartialEq)]
+pub struct WindowGeometry {
+    /// Mode of the window.
+    pub mode: PersistedWindowMode,
+    /// Position of the top-left corner in physical pixels, `None` if it's decided by the window manager.
+    pub position: Option<IVec2>,
+    /// Logical size of the window.
+    pub size: Vec2,
+    /// Name of the monitor the window is on, if known.
+    pub monitor: Option<String>,
+}
+
+/// Preferences with the geometry of every persisted window, by their [`PersistWindowGeometry`] key.
+/// See [`PreferencesWindowGeometryPlugin`].
+#[derive(Reflect, Clone, Debug, Default, PartialEq)]
+pub struct WindowGeometryPreferences {
+    /// Geometry of every persisted window.
+    pub windows: BTreeMap<String, WindowGeometry>,
+}
+
+/// Marks windows whose geometry has already been restored.
+#[derive(Component)]
+struct WindowGeometryRestored;
+
+#[derive(Resource)]
+struct WindowGeometryDebounce {
+    debounce: Duration,
+    pending: HashMap<String, (WindowGeometry, Duration)>,
+}
+
+struct MonitorInfo<'a> {
+    entity: Entity,
+    name: Option<&'a str>,
+    rect: IRect,
+    scale_factor: f64,
+    is_primary: bool,
+}
+
+fn monitor_infos<'a>(
+    monitors: impl IntoIterator<Item = (Entity, &'a Monitor, bool)>,
+) -> Vec<MonitorInfo<'a>> {
+    monitors
+        .into_iter()
+        .map(|(entity, monitor, is_primary)| MonitorInfo {
+            entity,
+            name: monitor.name.as_deref(),
+            rect: IRect::from_corners(
+                monitor.physical_position,
+                monitor.physical_position + monitor.physical_size().as_ivec2(),
+            ),
+            scale_factor: monitor.scale_factor,
+            is_primary,
+        })
+        .collect()
+}
+
+fn window_key(persist: Option<&PersistWindowGeometry>, is_primary: bool) -> Option<String> {
+    match persist {
+        Some(persist) => Some(persist.0.clone()),
+        None if is_primary => Some(PRIMARY_WINDOW_KEY.to_string()),
+        None => None,
+    }
+}
+
+type UnrestoredWindows<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut Window,
+        Option<&'static PersistWindowGeometry>,
+        Has<PrimaryWindow>,
+    ),
+    Without<WindowGeometryRestored>,
+>;
+
+type Monitors<'w, 's> = Query<'w, 's, (Entity, &'static Monitor, Has<PrimaryMonitor>)>;
+
+// Restores the windows that already exist, before the preferences resources are assigned
+fn restore_loaded_window_geometry(
+    commands: Commands,
+    storage_map: Option<ResMut<PreferencesSerializableMap>>,
+    windows: UnrestoredWindows,
+    monitors: Monitors,
+) {
+    let Some(mut storage_map) = storage_map else {
+        return;
+    };
+    storage_map
+        .bypass_change_detection()
+        .resolve_sparse(&WindowGeometryPreferences::default());
+    if let Some(preferences) = storage_map.get::<WindowGeometryPreferences>() {
+        restore_windows(commands, preferences, windows, &monitors);
+    }
+}
+
+fn restore_window_geometry(
+    commands: Commands,
+    preferences: PreferencesRef<WindowGeometryPreferences>,
+    windows: UnrestoredWindows,
+    monitors: Monitors,
+) {
+    restore_windows(commands, &preferences, windows, &monitors);
+}
+
+fn restore_windows(
+    mut commands: Commands,
+    preferences: &WindowGeometryPreferences,
+    mut windows: UnrestoredWindows,
+    monitors: &Monitors,
+) {
+    let monitors = monitor_infos(monitors);
+
+    for (entity, mut window, persist, is_primary) in &mut windows {
+        let Some(key) = window_key(persist, is_primary) else {
+            continue;
+        };
+        commands.entity(entity).insert(WindowGeometryRestored);
+
+        if let Some(geometry) = preferences.windows.get(&key) {
+            debug!("Restoring geometry of window {key}");
+            apply_geometry(&mut window, geometry, &monitors);
+        }
+    }
+}
+
+fn apply_geometry(window: &mut Window, geometry: &WindowGeometry, monitors: &[MonitorInfo]) {
+    let monitor = geometry
+        .monitor
+        .as_deref()
+        .and_then(|name| monitors.iter().find(|monitor| monitor.name == Some(name)));
+
+    let monitor_selection = monitor
+        .map(|monitor| MonitorSelection::Entity(monitor.entity))
+        .unwrap_or(MonitorSelection::Current);
+    window.mode = geometry.mode.window_mode(monitor_selection);
+
+    if geometry.size.x > 0. && geometry.size.y > 0. {
+        window.resolution.set(geometry.size.x, geometry.size.y);
+    }
+
+    if let Some(position) = geometry.position {
+        let scale_factor = monitor
+            .map(|monitor| monitor.scale_factor as f32)
+            .unwrap_or(window.scale_factor());
+        let physical_size = (geometry.size * scale_factor).as_ivec2();
+        window.position = WindowPosition::At(clamp_position(
+            position,
+            physical_size,
+            monitors,
+            geometry.monitor.as_deref(),
+        ));
+    }
+}
+
+/// Clamps a window position so the window is fully inside a monitor, if it fits.
+/// The preferred monitor is used if it exists, then the one containing the position, then the primary one.
+fn clamp_position(
+    position: IVec2,
+    physical_size: IVec2,
+    monitors: &[MonitorInfo],
+    preferred_monitor: Option<&str>,
+) -> IVec2 {
+    let target = preferred_monitor
+        .and_then(|name| monitors.iter().find(|monitor| monitor.name == Some(name)))
+        .or_else(|| {
+            monitors
+                .iter()
+                .find(|monitor| monitor.rect.contains(position))
+        })
+        .or_else(|| monitors.iter().find(|monitor| monitor.is_primary))
+        .or_else(|| monitors.first());
+
+    let Some(target) = target else {
+        return position;
+    };
+
+    let max = (target.rect.max - physical_size).max(target.rect.min);
+    position.clamp(target.rect.min, max)
+}
+
+fn window_geometry(window: &Window, monitors: &[MonitorInfo]) -> WindowGeometry {
+    let (mode, monitor_selection) = match window.mode {
+        WindowMode::Windowed => (PersistedWindowMode::Windowed, None),
+        WindowMode::BorderlessFullscreen(selection) => {
+            (PersistedWindowMode::BorderlessFullscreen, Some(selection))
+        }
+        WindowMode::SizedFullscreen(selection) => {
+            (PersistedWindowMode::SizedFullscreen, Some(selection))
+        }
+        WindowMode::Fullscreen(selection) => (PersistedWindowMode::Fullscreen, Some(selection)),
+    };
+
+    let position = match window.position {
+        WindowPosition::At(position) => Some(position),
+        _ => None,
+    };
+
+    let monitor = match monitor_selection {
+        Some(MonitorSelection::Entity(entity)) => {
+            monitors.iter().find(|monitor| monitor.entity == entity)
+        }
+        Some(MonitorSelection::Primary) => monitors.iter().find(|monitor| monitor.is_primary),
+        Some(MonitorSelection::Index(index)) => monitors.get(index),
+        _ => position.and_then(|position| {
+            monitors
+                .iter()
+                .find(|monitor| monitor.rect.contains(position))
+        }),
+    };
+
+    WindowGeometry {
+        mode,
+        position,
+        size: window.resolution.size(),
+        monitor: monitor.and_then(|monitor| monitor.name.map(String::from)),
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn track_window_geometry(
+    time: Option<Res<Time<Real>>>,
+    mut debounce: ResMut<WindowGeometryDebounce>,
+    mut preferences: Preferences<WindowGeometryPreferences>,
+    windows: Query<
+        (&Window, Option<&PersistWindowGeometry>, Has<PrimaryWindow>),
+        (Changed<Window>, With<WindowGeometryRestored>),
+    >,
+    monitors: Query<(Entity, &Monitor, Has<PrimaryMonitor>)>,
+    mut app_exit: EventReader<AppExit>,
+) {
+    let delta = time.map(|time| time.delta()).unwrap_or_default();
+    let is_exiting = !app_exit.is_empty();
+    app_exit.clear();
+
+    for (_, remaining) in debounce.pending.values_mut() {
+        *remaining = remaining.saturating_sub(delta);
+    }
+
+    let monitors = monitor_infos(&monitors);
+    let debounce_duration = debounce.debounce;
+    for (window, persist, is_primary) in &windows {
+        if let Some(key) = window_key(persist, is_primary) {
+            let geometry = window_geometry(window, &monitors);
+            debounce.pending.insert(key, (geometry, debounce_duration));
+        }
+    }
+
+    let ready: Vec<_> = debounce
+        .pending
+        .iter()
+        .filter(|(_, (_, remaining))| is_exiting || remaining.is_zero())
+        .map(|(key, _)| key.clone())
+        .collect();
+
+    for key in ready {
+        let (geometry, _) = debounce.pending.remove(&key).expect("Pending geometry");
+        if preferences.windows.get(&key) != Some(&geometry) {
+            debug!("Storing geometry of window {key}");
+            preferences.windows.insert(key, geometry);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MonitorInfo, WindowGeometryRestored, clamp_position};
+    use crate::serializable_map::PreferencesSerializableMap;
+    use crate::{
+        PersistWindowGeometry, PersistedWindowMode, PreferencesPlugin, PreferencesResource,
+        PreferencesWindowGeometryPlugin, WindowGeometry, WindowGeometryPreferences,
+    };
+    use bevy::prelude::*;
+    use bevy::window::{
+        Monitor, MonitorSelection, PrimaryMonitor, PrimaryWindow, WindowMode, WindowPosition,
+    };
+    use std::time::Duration;
+
+    fn monitor(name: &str, position: IVec2) -> Monitor {
+        Monitor {
+            name: Some(name.into()),
+            physical_height: 1080,
+            physical_width: 1920,
+            physical_position: position,
+            refresh_rate_millihertz: None,
+            scale_factor: 1.0,
+            video_modes: Vec::new(),
+        }
+    }
+
+    fn new_app(geometry: WindowGeometry) -> App {
+        let mut app = App::new();
+        app.add_plugins(PreferencesWindowGeometryPlugin::default())
+            .insert_resource(PreferencesResource::new(WindowGeometryPreferences {
+                windows: [("settings".to_string(), geometry)].into(),
+            }))
+            .insert_resource(Time::<Real>::default());
+        app.world_mut()
+            .resource_mut::<Time<Real>>()
+            .update_with_duration(Duration::ZERO);
+        app.world_mut()
+            .spawn((monitor("Left", IVec2::ZERO), PrimaryMonitor));
+        app.world_mut().spawn(monitor("Right", IVec2::new(1920, 0)));
+        app
+    }
+
+    fn advance(app: &mut App, duration: Duration) {
+        app.world_mut()
+            .resource_mut::<Time<Real>>()
+            .update_with_duration(duration);
+        app.update();
+    }
+
+    fn stored_geometry(app: &App) -> Option<&WindowGeometry> {
+        app.world()
+            .resource::<PreferencesResource<WindowGeometryPreferences>>()
+            .windows
+            .get("settings")
+    }
+
+    #[test]
+    fn test_clamp_position() {
+        let monitors = [
+            MonitorInfo {
+                entity: Entity::PLACEHOLDER,
+                name: Some("Left"),
+                rect: IRect::new(0, 0, 1920, 1080),
+                scale_factor: 1.0,
+                is_primary: true,
+            },
+            MonitorInfo {
+                entity: Entity::PLACEHOLDER,
+                name: Some("Right"),
+                rect: IRect::new(1920, 0, 3840, 1080),
+                scale_factor: 1.0,
+                is_primary: false,
+            },
+        ];
+        let size = IVec2::new(800, 600);
+
+        assert_eq!(
+            clamp_position(IVec2::new(2000, 100), size, &monitors, None),
+            IVec2::new(2000, 100)
+        );
+        assert_eq!(
+            clamp_position(IVec2::new(3500, 900), size, &monitors, Some("Right")),
+            IVec2::new(3040, 480)
+        );
+        // The monitor no longer exists
+        assert_eq!(
+            clamp_position(IVec2::new(5000, -200), size, &monitors, Some("Gone")),
+            IVec2::new(1120, 0)
+        );
+        assert_eq!(
+            clamp_position(IVec2::new(5000, -200), size, &[], None),
+            IVec2::new(5000, -200)
+        );
+    }
+
+    #[test]
+    fn test_geometry_is_restored_onto_existing_monitors() {
+        let mut app = new_app(WindowGeometry {
+            mode: PersistedWindowMode::BorderlessFullscreen,
+            position: Some(IVec2::new(3800, 50)),
+            size: Vec2::new(800., 600.),
+            monitor: Some("Right".into()),
+        });
+        let window = app
+            .world_mut()
+            .spawn((Window::default(), PersistWindowGeometry("settings".into())))
+            .id();
+        let other_window = app.world_mut().spawn(Window::default()).id();
+
+        advance(&mut app, Duration::ZERO);
+
+        let window = app.world().entity(window);
+        assert!(window.contains::<WindowGeometryRestored>());
+        let window = window.get::<Window>().unwrap();
+        assert_eq!(window.position, WindowPosition::At(IVec2::new(3040, 50)));
+        assert_eq!(window.resolution.size(), Vec2::new(800., 600.));
+        assert!(matches!(
+            window.mode,
+            WindowMode::BorderlessFullscreen(MonitorSelection::Entity(_))
+        ));
+        assert!(
+            !app.world()
+                .entity(other_window)
+                .contains::<WindowGeometryRestored>()
+        );
+    }
+
+    #[test]
+    fn test_existing_windows_are_restored_before_startup() {
+        let mut app = App::new();
+        app.add_plugins(PreferencesPlugin::with_no_persistence())
+            .add_plugins(PreferencesWindowGeometryPlugin::default());
+
+        let mut storage_map = {
+            let type_registry_arc = app.world().resource::<AppTypeRegistry>().0.clone();
+            PreferencesSerializableMap::empty(type_registry_arc)
+        };
+        storage_map.set(WindowGeometryPreferences {
+            windows: [(
+                "primary".to_string(),
+                WindowGeometry {
+                    position: Some(IVec2::new(100, 200)),
+                    size: Vec2::new(800., 600.),
+                    ..default()
+                },
+            )]
+            .into(),
+        });
+        app.insert_resource(storage_map);
+        app.world_mut()
+            .spawn((monitor("Left", IVec2::ZERO), PrimaryMonitor));
+        app.world_mut().spawn((Window::default(), PrimaryWindow));
+
+        app.add_systems(Startup, |window: Single<&Window, With<PrimaryWindow>>| {
+            assert_eq!(window.position, WindowPosition::At(IVec2::new(100, 200)));
+            assert_eq!(window.resolution.size(), Vec2::new(800., 600.));
+        });
+        app.update();
+    }
+
+    #[test]
+    fn test_changes_are_debounced() {
+        let mut app = new_app(WindowGeometry::default());
+        let window = app
+            .world_mut()
+            .spawn((Window::default(), PersistWindowGeometry("settings".into())))
+            .id();
+        advance(&mut app, Duration::ZERO);
+
+        for x in 0..5 {
+            app.world_mut()
+                .get_mut::<Window>(window)
+                .unwrap()
+                .position
+                .set(IVec2::new(100 + x, 100));
+            advance(&mut app, Duration::from_millis(100));
+        }
+        assert_eq!(stored_geometry(&app).unwrap().position, None);
+
+        advance(&mut app, Duration::from_millis(600));
+        let stored = stored_geometry(&app).unwrap();
+        assert_eq!(stored.position, Some(IVec2::new(104, 100)));
+        assert_eq!(stored.monitor.as_deref(), Some("Left"));
+    }
+}
@@ -3,7 +3,9 @@ mod utils;
 
 use bevy::prelude::*;
 use bevy::utils::HashMap;
-use bevy_simple_preferences::{Preferences, PreferencesStorageType, RegisterPreferencesExt};
+use bevy_simple_preferences::{
+    Preferences, PreferencesPlugin, PreferencesStorageType, RegisterPreferencesExt,
+};
 use rand::random;
 use utils::*;
 
@@ -74,6 +76,80 @@ fn preferences_plugin_reads_and_writes_to_disk() {
     );
 }
 
+#[derive(Reflect, PartialEq, Clone, Debug)]
+struct SparseWindowPreferences {
+    width: u32,
+    height: u32,
+}
+
+#[derive(Reflect, PartialEq, Clone, Debug)]
+struct SparsePreferences {
+    volume: u32,
+    window: SparseWindowPreferences,
+}
+
+#[cfg(not(target_family = "wasm"))]
+#[test]
+fn sparse_storage_only_stores_changed_fields() {
+    let temp_dir = temp_dir();
+    let sparse_plugin = || {
+        PreferencesPlugin::persisted_with_app_name("PreferencesTest")
+            .with_storage_type(PreferencesStorageType::FileSystemWithParentDirectory(
+                temp_dir.path().into(),
+            ))
+            .with_sparse_storage()
+    };
+
+    {
+        create_test_app_with_plugin(sparse_plugin())
+            .register_preferences_with_default_value(SparsePreferences {
+                volume: 5,
+                window: SparseWindowPreferences {
+                    width: 800,
+                    height: 600,
+                },
+            })
+            .add_systems(Update, |mut preferences: Preferences<SparsePreferences>| {
+                preferences.window.width = 1024;
+            })
+            .run();
+    }
+
+    let contents =
+        std::fs::read_to_string(temp_dir.path().join("PreferencesTest/preferences.toml")).unwrap();
+    // Besides the app metadata, only the modified field is stored
+    let (preferences, metadata) = contents
+        .split_once("\n[\"bevy_simple_preferences::StoredAppMetadata\"]\n")
+        .unwrap();
+    assert_eq!(preferences, "[SparsePreferences.window]\nwidth = 1024\n");
+    assert!(metadata.contains("launch_count = 1\n"));
+
+    // New defaults reach the fields that were never modified
+    {
+        create_test_app_with_plugin(sparse_plugin())
+            .register_preferences_with_default_value(SparsePreferences {
+                volume: 7,
+                window: SparseWindowPreferences {
+                    width: 800,
+                    height: 720,
+                },
+            })
+            .add_systems(Update, |preferences: Preferences<SparsePreferences>| {
+                assert_eq!(
+                    *preferences,
+                    SparsePreferences {
+                        volume: 7,
+                        window: SparseWindowPreferences {
+                            width: 1024,
+                            height: 720,
+                        },
+                    }
+                );
+            })
+            .run();
+    }
+}
+
 #[cfg(target_family = "wasm")]
 #[wasm_bindgen_test]
 fn preferences_plugin_reads_and_writes_to_local_storage() {
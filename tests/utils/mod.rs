@@ -8,13 +8,17 @@ pub fn temp_dir() -> tempfile::TempDir {
 }
 
 pub fn create_test_app(storage_type: PreferencesStorageType) -> App {
+    create_test_app_with_plugin(
+        PreferencesPlugin::persisted_with_app_name("PreferencesTest")
+            .with_storage_type(storage_type),
+    )
+}
+
+pub fn create_test_app_with_plugin(preferences_plugin: PreferencesPlugin) -> App {
     let mut app = App::new();
 
     app.add_plugins(MinimalPlugins)
-        .add_plugins(
-            PreferencesPlugin::persisted_with_app_name("PreferencesTest")
-                .with_storage_type(storage_type),
-        )
+        .add_plugins(preferences_plugin)
         .add_systems(
             PostUpdate,
             |mut app_exit: EventWriter<AppExit>, frame_count: Res<FrameCount>| {